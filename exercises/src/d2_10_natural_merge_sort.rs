@@ -0,0 +1,339 @@
+use crate::{
+    d2_14_sorted_data::gallop,
+    d2_5_sorter::{partial_compare, Sorter},
+};
+use std::cmp::Ordering;
+
+// Adaptive merge sort (Timsort-style): instead of splitting the collection blindly, the already
+// sorted sequences ("runs") are detected and merged, so that partially sorted collections require
+// fewer comparisons; a sorted (or reversed) collection is a single run, so it's O(n).
+//
+// - strictly descending runs are reversed (strictly, so that equal elements are not swapped);
+// - runs shorter than a minimum length are extended with an insertion sort, since merging many tiny
+//   runs is inefficient;
+// - the runs are pushed on a stack, and merged so that their lengths are kept balanced (each run is
+//   longer than the sum of the two above it), which guarantees O(n * log(n));
+// - when merging, if one side "wins" many times in a row, its elements are likely to be already in
+//   order, so the merge switches to galloping (exponential search) to find them in bulk.
+//
+// Returns the number of runs found. Merging requires a buffer; since the elements are cloned only in
+// it, and swapped elsewhere, `Clone` is required rather than `Copy`.
+
+// Consecutive wins of one side, after which the merge gallops.
+//
+const MIN_GALLOP: usize = 7;
+
+pub fn natural_merge_sort<T: PartialOrd + Clone>(collection: &mut [T]) -> usize {
+    natural_merge_sort_by(collection, partial_compare)
+}
+
+pub fn natural_merge_sort_by<T: Clone, F: Fn(&T, &T) -> Ordering>(
+    collection: &mut [T],
+    compare: F,
+) -> usize {
+    let min_run = min_run_length(collection.len());
+
+    // Each entry is the (start, length) of a run.
+    //
+    let mut runs: Vec<(usize, usize)> = vec![];
+    let mut runs_found = 0;
+    let mut start = 0;
+
+    while start < collection.len() {
+        let mut run_len = find_run(&mut collection[start..], &compare);
+
+        if run_len < min_run {
+            let extended_len = min_run.min(collection.len() - start);
+            insertion_sort(
+                &mut collection[start..start + extended_len],
+                run_len,
+                &compare,
+            );
+            run_len = extended_len;
+        }
+
+        runs.push((start, run_len));
+        runs_found += 1;
+        start += run_len;
+
+        collapse_runs(collection, &mut runs, &compare, false);
+    }
+
+    collapse_runs(collection, &mut runs, &compare, true);
+
+    runs_found
+}
+
+// Same as Timsort: for small collections, the whole collection; otherwise, a value in 32..=64, such
+// that the number of runs is a power of two, or slightly less, which keeps the final merges balanced.
+//
+fn min_run_length(mut len: usize) -> usize {
+    let mut remainder = 0;
+
+    while len >= 64 {
+        remainder |= len & 1;
+        len >>= 1;
+    }
+
+    len + remainder
+}
+
+// Returns the length of the run at the start of the collection, which is reversed, if descending.
+//
+fn find_run<T, F: Fn(&T, &T) -> Ordering>(collection: &mut [T], compare: &F) -> usize {
+    if collection.len() <= 1 {
+        return collection.len();
+    }
+
+    let mut end = 2;
+
+    if compare(&collection[1], &collection[0]) == Ordering::Less {
+        while end < collection.len()
+            && compare(&collection[end], &collection[end - 1]) == Ordering::Less
+        {
+            end += 1;
+        }
+
+        collection[..end].reverse();
+    } else {
+        while end < collection.len()
+            && compare(&collection[end], &collection[end - 1]) != Ordering::Less
+        {
+            end += 1;
+        }
+    }
+
+    end
+}
+
+// The first `sorted_len` elements are already sorted.
+//
+fn insertion_sort<T, F: Fn(&T, &T) -> Ordering>(
+    collection: &mut [T],
+    sorted_len: usize,
+    compare: &F,
+) {
+    for start in sorted_len.max(1)..collection.len() {
+        let mut i = start;
+
+        while i > 0 && compare(&collection[i], &collection[i - 1]) == Ordering::Less {
+            collection.swap(i - 1, i);
+            i -= 1;
+        }
+    }
+}
+
+// Merges the runs on top of the stack, until the lengths invariants are restored (or, if `force` is
+// set, until one run is left). With X, Y, Z being the top three runs (Z on top), the invariants are
+// |X| > |Y| + |Z| and |Y| > |Z|; the fourth run from the top is checked as well, since checking three
+// runs only is not sufficient to guarantee the invariant on the whole stack.
+//
+fn collapse_runs<T: Clone, F: Fn(&T, &T) -> Ordering>(
+    collection: &mut [T],
+    runs: &mut Vec<(usize, usize)>,
+    compare: &F,
+    force: bool,
+) {
+    while runs.len() > 1 {
+        let mut n = runs.len() - 2;
+        let len = |i: usize| runs[i].1;
+
+        let unbalanced = (n > 0 && len(n - 1) <= len(n) + len(n + 1))
+            || (n > 1 && len(n - 2) <= len(n - 1) + len(n));
+
+        if unbalanced || force {
+            // Merge the smaller of the two runs adjacent to Y.
+            //
+            if n > 0 && len(n - 1) < len(n + 1) {
+                n -= 1;
+            }
+        } else if len(n) > len(n + 1) {
+            break;
+        }
+
+        merge_at(collection, runs, n, compare);
+    }
+}
+
+// Merges the runs `n` and `n + 1`.
+//
+fn merge_at<T: Clone, F: Fn(&T, &T) -> Ordering>(
+    collection: &mut [T],
+    runs: &mut Vec<(usize, usize)>,
+    n: usize,
+    compare: &F,
+) {
+    let (start, len_1) = runs[n];
+    let (_, len_2) = runs[n + 1];
+
+    runs[n] = (start, len_1 + len_2);
+    runs.remove(n + 1);
+
+    let merged_run = &mut collection[start..start + len_1 + len_2];
+    let (run_1, run_2) = merged_run.split_at(len_1);
+
+    // The elements of the first run not greater than the first of the second are already in place, as
+    // are the elements of the second run not lower than the last of the first.
+    //
+    let skip_1 = gallop(run_1, |value| {
+        compare(value, &run_2[0]) != Ordering::Greater
+    });
+    let keep_2 = gallop(run_2, |value| {
+        compare(value, &run_1[len_1 - 1]) == Ordering::Less
+    });
+
+    merge(
+        &mut merged_run[skip_1..len_1 + keep_2],
+        len_1 - skip_1,
+        compare,
+    );
+}
+
+// Stable merge of the two sorted parts (split at `len_1`) of the collection. The first part is moved
+// to a buffer, then the elements are moved (by swapping) to their final position, from the start.
+//
+fn merge<T: Clone, F: Fn(&T, &T) -> Ordering>(collection: &mut [T], len_1: usize, compare: &F) {
+    let mut buffer = collection[..len_1].to_vec();
+
+    // The destination (`k`) never overtakes the next element of the second part (`j`).
+    //
+    let (mut i, mut j, mut k) = (0, len_1, 0);
+    let (mut wins_1, mut wins_2) = (0, 0);
+
+    while i < buffer.len() && j < collection.len() {
+        if wins_1 >= MIN_GALLOP {
+            let count = gallop(&buffer[i..], |value| {
+                compare(value, &collection[j]) != Ordering::Greater
+            });
+
+            for _ in 0..count {
+                std::mem::swap(&mut collection[k], &mut buffer[i]);
+                i += 1;
+                k += 1;
+            }
+
+            wins_1 = 0;
+        } else if wins_2 >= MIN_GALLOP {
+            let count = gallop(&collection[j..], |value| {
+                compare(value, &buffer[i]) == Ordering::Less
+            });
+
+            for _ in 0..count {
+                collection.swap(k, j);
+                j += 1;
+                k += 1;
+            }
+
+            wins_2 = 0;
+        } else if compare(&collection[j], &buffer[i]) == Ordering::Less {
+            collection.swap(k, j);
+            j += 1;
+            k += 1;
+            wins_2 += 1;
+            wins_1 = 0;
+        } else {
+            std::mem::swap(&mut collection[k], &mut buffer[i]);
+            i += 1;
+            k += 1;
+            wins_1 += 1;
+            wins_2 = 0;
+        }
+    }
+
+    // If the second part is exhausted, the rest of the first goes at the end; otherwise, the rest of
+    // the second is already in place.
+    //
+    for value in buffer.drain(i..) {
+        collection[k] = value;
+        k += 1;
+    }
+}
+
+pub struct NaturalMergeSort;
+
+impl<T: Clone> Sorter<T> for NaturalMergeSort {
+    fn sort_by<F: Fn(&T, &T) -> Ordering + Sync>(&self, collection: &mut [T], compare: F) {
+        natural_merge_sort_by(collection, compare);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_sort, test_sort_properties};
+
+    test_sort!(test_natural_merge_sort, collection, {
+        natural_merge_sort(&mut collection);
+    });
+
+    #[test]
+    fn test_natural_merge_sort_runs() {
+        let mut collection = (0..1000).collect::<Vec<_>>();
+
+        assert_eq!(natural_merge_sort(&mut collection), 1);
+
+        collection.reverse();
+
+        assert_eq!(natural_merge_sort(&mut collection), 1);
+        assert_eq!(collection, (0..1000).collect::<Vec<_>>());
+
+        // Four ascending blocks, longer than the minimum run.
+        //
+        let mut collection = (0..4)
+            .flat_map(|block| (0..250).map(move |i| i * 4 + block))
+            .collect::<Vec<_>>();
+
+        assert_eq!(natural_merge_sort(&mut collection), 4);
+        assert_eq!(collection, (0..1000).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_natural_merge_sort_large() {
+        // Partially sorted: sorted blocks of random length, some reversed, with random values.
+        //
+        let mut collection = vec![];
+
+        while collection.len() < 100_000 {
+            let mut block = (0..rand::random::<usize>() % 2000)
+                .map(|_| rand::random::<u16>())
+                .collect::<Vec<_>>();
+            block.sort();
+
+            if rand::random() {
+                block.reverse();
+            }
+
+            collection.extend(block);
+        }
+
+        let mut expected_collection = collection.clone();
+        expected_collection.sort();
+
+        natural_merge_sort(&mut collection);
+
+        assert_eq!(collection, expected_collection);
+    }
+
+    #[test]
+    fn test_natural_merge_sort_stable() {
+        let mut collection = (0..10_000)
+            .map(|i| (rand::random::<u8>() % 16, i.to_string()))
+            .collect::<Vec<_>>();
+
+        let mut expected_collection = collection.clone();
+        expected_collection.sort_by_key(|(key, _)| *key);
+
+        NaturalMergeSort.sort_by_key(&mut collection, |(key, _)| *key);
+
+        assert_eq!(collection, expected_collection);
+    }
+
+    test_sort_properties!(
+        test_natural_merge_sort_properties,
+        collection,
+        {
+            natural_merge_sort(&mut collection);
+        },
+        stable = true
+    );
+}
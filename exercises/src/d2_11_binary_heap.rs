@@ -0,0 +1,252 @@
+use crate::d2_5_sorter::{partial_compare, Sorter};
+use std::cmp::Ordering;
+
+// Binary heap, stored as a complete binary tree in a vector: the children of the node `i` are at
+// `2i + 1` and `2i + 2`, and each node is not lower than its children (according to the comparator),
+// so the root is the greatest element.
+//
+// A min heap is a max heap with the inverted comparator.
+//
+pub struct BinaryHeap<T, F = fn(&T, &T) -> Ordering> {
+    data: Vec<T>,
+    compare: F,
+}
+
+impl<T: PartialOrd> BinaryHeap<T> {
+    pub fn max_heap() -> Self {
+        Self::with_comparator(partial_compare)
+    }
+
+    pub fn min_heap() -> Self {
+        Self::with_comparator(|a, b| partial_compare(b, a))
+    }
+
+    pub fn max_heap_from_vec(data: Vec<T>) -> Self {
+        Self::from_vec_by(data, partial_compare)
+    }
+
+    pub fn min_heap_from_vec(data: Vec<T>) -> Self {
+        Self::from_vec_by(data, |a, b| partial_compare(b, a))
+    }
+}
+
+impl<T, F: Fn(&T, &T) -> Ordering> BinaryHeap<T, F> {
+    pub fn with_comparator(compare: F) -> Self {
+        Self {
+            data: vec![],
+            compare,
+        }
+    }
+
+    // Heapify: the leaves are already heaps, so the nodes above them are sifted down, from the
+    // bottom; this is O(n), rather than O(n * log(n)) of pushing the elements one by one.
+    //
+    pub fn from_vec_by(mut data: Vec<T>, compare: F) -> Self {
+        for i in (0..data.len() / 2).rev() {
+            sift_down(&mut data, i, &compare);
+        }
+
+        Self { data, compare }
+    }
+
+    pub fn push(&mut self, value: T) {
+        self.data.push(value);
+
+        let mut i = self.data.len() - 1;
+
+        while i > 0 {
+            let parent = (i - 1) / 2;
+
+            if (self.compare)(&self.data[i], &self.data[parent]) != Ordering::Greater {
+                break;
+            }
+
+            self.data.swap(i, parent);
+            i = parent;
+        }
+    }
+
+    // The root is swapped with the last element, which is then sifted down.
+    //
+    pub fn pop(&mut self) -> Option<T> {
+        if self.data.is_empty() {
+            return None;
+        }
+
+        let last = self.data.len() - 1;
+        self.data.swap(0, last);
+
+        let root = self.data.pop();
+
+        sift_down(&mut self.data, 0, &self.compare);
+
+        root
+    }
+
+    pub fn peek(&self) -> Option<&T> {
+        self.data.first()
+    }
+
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    // Sorted in ascending order, according to the comparator (so, for a min heap, in descending
+    // order).
+    //
+    pub fn into_sorted_vec(mut self) -> Vec<T> {
+        sort_heap(&mut self.data, &self.compare);
+        self.data
+    }
+}
+
+// In-place heapsort: the collection is arranged as a max heap, then the root (max) is repeatedly
+// swapped with the last element of the heap, which is then shrunk.
+//
+pub fn heap_sort<T: PartialOrd>(collection: &mut [T]) {
+    heap_sort_by(collection, partial_compare)
+}
+
+pub fn heap_sort_by<T, F: Fn(&T, &T) -> Ordering>(collection: &mut [T], compare: F) {
+    for i in (0..collection.len() / 2).rev() {
+        sift_down(collection, i, &compare);
+    }
+
+    sort_heap(collection, &compare);
+}
+
+fn sort_heap<T, F: Fn(&T, &T) -> Ordering>(heap: &mut [T], compare: &F) {
+    for end in (1..heap.len()).rev() {
+        heap.swap(0, end);
+        sift_down(&mut heap[..end], 0, compare);
+    }
+}
+
+fn sift_down<T, F: Fn(&T, &T) -> Ordering>(heap: &mut [T], mut i: usize, compare: &F) {
+    loop {
+        let left = 2 * i + 1;
+        let right = left + 1;
+
+        let mut largest = i;
+
+        if left < heap.len() && compare(&heap[left], &heap[largest]) == Ordering::Greater {
+            largest = left;
+        }
+        if right < heap.len() && compare(&heap[right], &heap[largest]) == Ordering::Greater {
+            largest = right;
+        }
+
+        if largest == i {
+            return;
+        }
+
+        heap.swap(i, largest);
+        i = largest;
+    }
+}
+
+pub struct HeapSort;
+
+impl<T> Sorter<T> for HeapSort {
+    fn sort_by<F: Fn(&T, &T) -> Ordering + Sync>(&self, collection: &mut [T], compare: F) {
+        heap_sort_by(collection, compare)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_sort, test_sort_properties};
+
+    test_sort!(test_heap_sort, collection, heap_sort(&mut collection));
+
+    test_sort!(
+        test_heap_sort_by,
+        collection,
+        HeapSort.sort_by(&mut collection, |a, b| a.cmp(b))
+    );
+
+    test_sort!(
+        test_into_sorted_vec,
+        collection,
+        collection = BinaryHeap::max_heap_from_vec(collection).into_sorted_vec()
+    );
+
+    #[test]
+    fn test_max_heap() {
+        let mut heap = BinaryHeap::max_heap();
+
+        assert!(heap.is_empty());
+        assert_eq!(heap.pop(), None);
+
+        for value in &[5, 1, 8, 3, 9, 2, 8] {
+            heap.push(*value);
+        }
+
+        assert_eq!(heap.len(), 7);
+        assert_eq!(heap.peek(), Some(&9));
+
+        let popped_values = std::iter::from_fn(|| heap.pop()).collect::<Vec<_>>();
+
+        assert_eq!(popped_values, vec![9, 8, 8, 5, 3, 2, 1]);
+    }
+
+    #[test]
+    fn test_min_heap() {
+        let mut heap = BinaryHeap::min_heap_from_vec(vec![5, 1, 8, 3, 9, 2, 8]);
+
+        assert_eq!(heap.peek(), Some(&1));
+
+        heap.push(0);
+
+        assert_eq!(heap.pop(), Some(0));
+        assert_eq!(heap.pop(), Some(1));
+        assert_eq!(heap.into_sorted_vec(), vec![9, 8, 8, 5, 3, 2]);
+    }
+
+    #[test]
+    fn test_heap_with_comparator() {
+        let mut heap =
+            BinaryHeap::with_comparator(|a: &(&str, u32), b: &(&str, u32)| a.1.cmp(&b.1));
+
+        heap.push(("low", 1));
+        heap.push(("high", 10));
+        heap.push(("mid", 5));
+
+        let popped_names = std::iter::from_fn(|| heap.pop())
+            .map(|(name, _)| name)
+            .collect::<Vec<_>>();
+
+        assert_eq!(popped_names, vec!["high", "mid", "low"]);
+    }
+
+    #[test]
+    fn test_heap_large() {
+        let collection = (0..10_000)
+            .map(|_| rand::random::<i32>())
+            .collect::<Vec<_>>();
+
+        let mut expected_collection = collection.clone();
+        expected_collection.sort();
+
+        let mut heap = BinaryHeap::min_heap();
+
+        for value in collection {
+            heap.push(value);
+        }
+
+        let popped_values = std::iter::from_fn(|| heap.pop()).collect::<Vec<_>>();
+
+        assert_eq!(popped_values, expected_collection);
+    }
+
+    test_sort_properties!(
+        test_heap_sort_properties,
+        collection,
+        heap_sort(&mut collection)
+    );
+}
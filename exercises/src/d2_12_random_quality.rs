@@ -0,0 +1,434 @@
+use rand_core::RngCore;
+
+// Statistical tests for random generators. Each test computes a statistic over a sample of uniform
+// values in [0, 1), and the probability (p-value) of a statistic at least as extreme, if the values
+// were truly random; very low p-values mean that the generator is likely not random.
+//
+// Generators are adapted to uniform values either as iterators of integers in a known range (like
+// `RandGen` and `BigGen`), or as `RngCore`.
+
+// Significance level: below this p-value, a test fails.
+//
+pub const ALPHA: f64 = 0.001;
+
+#[derive(Debug)]
+pub struct TestResult {
+    pub name: &'static str,
+    pub statistic: f64,
+    pub p_value: f64,
+}
+
+impl TestResult {
+    pub fn passed(&self) -> bool {
+        self.p_value >= ALPHA
+    }
+}
+
+pub fn uniform_from_iter<I: Iterator<Item = usize>>(
+    iter: I,
+    range: usize,
+) -> impl Iterator<Item = f64> {
+    iter.map(move |value| value as f64 / range as f64)
+}
+
+// The 53 most significant bits of each value are used (the precision of an f64).
+//
+pub fn uniform_from_rng<R: RngCore>(mut rng: R) -> impl Iterator<Item = f64> {
+    std::iter::repeat_with(move || (rng.next_u64() >> 11) as f64 / (1_u64 << 53) as f64)
+}
+
+// Runs all the tests on the same sample.
+//
+pub fn run_battery(sample: &[f64]) -> Vec<TestResult> {
+    vec![
+        chi_square_test(sample),
+        serial_correlation_test(sample),
+        runs_test(sample),
+        gap_test(sample),
+        birthday_spacings_test(sample),
+    ]
+}
+
+// Buckets for the chi-square test.
+//
+const CHI_SQUARE_BUCKETS: usize = 100;
+
+// The values are counted in equally sized buckets; the statistic is the sum of the squared
+// differences from the expected counts (relative to them), which follows a chi-square distribution.
+//
+pub fn chi_square_test(sample: &[f64]) -> TestResult {
+    let mut counts = vec![0; CHI_SQUARE_BUCKETS];
+
+    for value in sample {
+        counts[bucket(*value, CHI_SQUARE_BUCKETS)] += 1;
+    }
+
+    let expected_count = sample.len() as f64 / CHI_SQUARE_BUCKETS as f64;
+    let expected_counts = vec![expected_count; CHI_SQUARE_BUCKETS];
+
+    let statistic = chi_square(&counts, &expected_counts);
+
+    TestResult {
+        name: "chi-square",
+        statistic,
+        p_value: chi_square_p_value(statistic, CHI_SQUARE_BUCKETS - 1),
+    }
+}
+
+// Correlation between each value and the next (circularly); for random values, it's close to 0,
+// and approximately normal, with standard deviation 1/sqrt(n).
+//
+pub fn serial_correlation_test(sample: &[f64]) -> TestResult {
+    let n = sample.len() as f64;
+
+    let sum = sample.iter().sum::<f64>();
+    let sum_squares = sample.iter().map(|value| value * value).sum::<f64>();
+    let sum_products = sample
+        .iter()
+        .zip(sample.iter().cycle().skip(1))
+        .map(|(value, next_value)| value * next_value)
+        .sum::<f64>();
+
+    let denominator = n * sum_squares - sum * sum;
+
+    // A constant sample has no defined correlation.
+    //
+    if denominator == 0.0 {
+        return TestResult {
+            name: "serial correlation",
+            statistic: f64::NAN,
+            p_value: 0.0,
+        };
+    }
+
+    let correlation = (n * sum_products - sum * sum) / denominator;
+
+    TestResult {
+        name: "serial correlation",
+        statistic: correlation,
+        p_value: normal_p_value(correlation * n.sqrt()),
+    }
+}
+
+// Wald-Wolfowitz runs test: a run is a maximal sequence of values on the same side of 0.5; too
+// few runs mean that values are clustered, too many that they alternate.
+//
+pub fn runs_test(sample: &[f64]) -> TestResult {
+    let above = sample.iter().filter(|value| **value >= 0.5).count() as f64;
+    let below = sample.len() as f64 - above;
+    let n = sample.len() as f64;
+
+    let runs = 1 + sample
+        .windows(2)
+        .filter(|pair| (pair[0] >= 0.5) != (pair[1] >= 0.5))
+        .count();
+
+    let mean = 2.0 * above * below / n + 1.0;
+    let variance = 2.0 * above * below * (2.0 * above * below - n) / (n * n * (n - 1.0));
+
+    let p_value = if variance > 0.0 {
+        normal_p_value((runs as f64 - mean) / variance.sqrt())
+    } else {
+        0.0
+    };
+
+    TestResult {
+        name: "runs",
+        statistic: runs as f64,
+        p_value,
+    }
+}
+
+// Interval of the gap test, and longest gap counted separately.
+//
+const GAP_INTERVAL: (f64, f64) = (0.4, 0.6);
+const GAP_MAX_LEN: usize = 20;
+
+// The gaps are the lengths of the sequences of values outside an interval, between two values
+// inside it; their lengths are geometrically distributed, and compared with a chi-square test.
+// Gaps of `GAP_MAX_LEN` or longer are counted together.
+//
+pub fn gap_test(sample: &[f64]) -> TestResult {
+    let (start, end) = GAP_INTERVAL;
+    let p = end - start;
+
+    let mut counts = vec![0; GAP_MAX_LEN + 1];
+    let mut gap_len = 0;
+
+    for value in sample {
+        if (start..end).contains(value) {
+            counts[gap_len.min(GAP_MAX_LEN)] += 1;
+            gap_len = 0;
+        } else {
+            gap_len += 1;
+        }
+    }
+
+    let gaps_count = counts.iter().sum::<usize>() as f64;
+
+    let mut expected_counts = (0..GAP_MAX_LEN)
+        .map(|len| gaps_count * p * (1.0 - p).powi(len as i32))
+        .collect::<Vec<_>>();
+    expected_counts.push(gaps_count * (1.0 - p).powi(GAP_MAX_LEN as i32));
+
+    let statistic = chi_square(&counts, &expected_counts);
+
+    TestResult {
+        name: "gap",
+        statistic,
+        p_value: chi_square_p_value(statistic, GAP_MAX_LEN),
+    }
+}
+
+// Birthdays per year, and days in a year; with these values, the expected number of duplicate
+// spacings per year is 2.
+//
+const BIRTHDAYS: usize = 512;
+const DAYS: f64 = (1 << 24) as f64;
+
+// Marsaglia's birthday spacings: the values are birthdays in a (long) year; the spacings between
+// the sorted birthdays are computed, and the duplicate spacings counted. For random values, the
+// duplicates follow a Poisson distribution with mean birthdays^3 / (4 * days); the sum over all
+// the years is tested.
+//
+// The values need at least 24 bits of resolution; generators with a smaller range always fail.
+//
+pub fn birthday_spacings_test(sample: &[f64]) -> TestResult {
+    let years = sample.len() / BIRTHDAYS;
+    let mut duplicates = 0;
+
+    for year in sample.chunks_exact(BIRTHDAYS) {
+        let mut birthdays = year
+            .iter()
+            .map(|value| (value * DAYS) as u64)
+            .collect::<Vec<_>>();
+        birthdays.sort_unstable();
+
+        let mut spacings = std::iter::once(birthdays[0])
+            .chain(birthdays.windows(2).map(|pair| pair[1] - pair[0]))
+            .collect::<Vec<_>>();
+        spacings.sort_unstable();
+
+        duplicates += spacings
+            .windows(2)
+            .filter(|pair| pair[0] == pair[1])
+            .count();
+    }
+
+    let mean = years as f64 * (BIRTHDAYS as f64).powi(3) / (4.0 * DAYS);
+
+    TestResult {
+        name: "birthday spacings",
+        statistic: duplicates as f64,
+        p_value: poisson_p_value(duplicates, mean),
+    }
+}
+
+fn bucket(value: f64, buckets: usize) -> usize {
+    ((value * buckets as f64) as usize).min(buckets - 1)
+}
+
+fn chi_square(counts: &[usize], expected_counts: &[f64]) -> f64 {
+    counts
+        .iter()
+        .zip(expected_counts)
+        .map(|(count, expected_count)| (*count as f64 - expected_count).powi(2) / expected_count)
+        .sum()
+}
+
+// Probability of a chi-square value greater than the statistic.
+//
+pub fn chi_square_p_value(statistic: f64, degrees_of_freedom: usize) -> f64 {
+    gamma_q(degrees_of_freedom as f64 / 2.0, statistic / 2.0)
+}
+
+// Two-sided probability of a standard normal value farther from 0 than `z`; this is `erfc(|z| /
+// sqrt(2))`, which is `Q(1/2, z^2 / 2)`.
+//
+pub fn normal_p_value(z: f64) -> f64 {
+    gamma_q(0.5, z * z / 2.0)
+}
+
+// Two-sided probability of a Poisson value at least as far from the mean as `count`. The CDF is
+// `P(X <= k) = Q(k + 1, mean)`.
+//
+pub fn poisson_p_value(count: usize, mean: f64) -> f64 {
+    let lower_tail = gamma_q(count as f64 + 1.0, mean);
+    let upper_tail = if count == 0 {
+        1.0
+    } else {
+        1.0 - gamma_q(count as f64, mean)
+    };
+
+    (2.0 * lower_tail.min(upper_tail)).min(1.0)
+}
+
+const GAMMA_EPSILON: f64 = 1e-14;
+const GAMMA_MAX_ITERATIONS: usize = 10_000;
+
+// Regularized upper incomplete gamma function `Q(a, x)`; computed with a series for small `x`, and
+// with a continued fraction otherwise (see Numerical Recipes, 6.2).
+//
+fn gamma_q(a: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        return 1.0;
+    }
+
+    let prefix = (-x + a * x.ln() - ln_gamma(a)).exp();
+
+    if x < a + 1.0 {
+        let mut term = 1.0 / a;
+        let mut sum = term;
+        let mut denominator = a;
+
+        for _ in 0..GAMMA_MAX_ITERATIONS {
+            denominator += 1.0;
+            term *= x / denominator;
+            sum += term;
+
+            if term.abs() < sum.abs() * GAMMA_EPSILON {
+                break;
+            }
+        }
+
+        1.0 - sum * prefix
+    } else {
+        // Modified Lentz's method.
+        //
+        let tiny = f64::MIN_POSITIVE / GAMMA_EPSILON;
+
+        let mut b = x + 1.0 - a;
+        let mut c = 1.0 / tiny;
+        let mut d = 1.0 / b;
+        let mut result = d;
+
+        for i in 1..GAMMA_MAX_ITERATIONS {
+            let an = -(i as f64) * (i as f64 - a);
+            b += 2.0;
+
+            d = an * d + b;
+            if d.abs() < tiny {
+                d = tiny;
+            }
+            c = b + an / c;
+            if c.abs() < tiny {
+                c = tiny;
+            }
+            d = 1.0 / d;
+
+            let delta = d * c;
+            result *= delta;
+
+            if (delta - 1.0).abs() < GAMMA_EPSILON {
+                break;
+            }
+        }
+
+        result * prefix
+    }
+}
+
+// Lanczos approximation (see Numerical Recipes, 6.1).
+//
+fn ln_gamma(x: f64) -> f64 {
+    const COEFFICIENTS: [f64; 6] = [
+        76.180_091_729_471_46,
+        -86.505_320_329_416_77,
+        24.014_098_240_830_91,
+        -1.231_739_572_450_155,
+        0.120_865_097_386_617_9e-2,
+        -0.539_523_938_495_3e-5,
+    ];
+
+    let tmp = x + 5.5 - (x + 0.5) * (x + 5.5).ln();
+    let mut series = 1.000_000_000_190_015;
+    let mut y = x;
+
+    for coefficient in COEFFICIENTS {
+        y += 1.0;
+        series += coefficient / y;
+    }
+
+    -tmp + (2.506_628_274_631_000_5 * series / x).ln()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::d2_3_quicksort::RandGen;
+    use rand::{rngs::mock::StepRng, rngs::StdRng, SeedableRng};
+
+    const SAMPLE_SIZE: usize = 100_000;
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-4,
+            "{} != {}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn test_p_values() {
+        assert_close(chi_square_p_value(3.841_459, 1), 0.05);
+        assert_close(chi_square_p_value(123.225_221, 99), 0.05);
+        assert_close(chi_square_p_value(0.0, 10), 1.0);
+
+        assert_close(normal_p_value(1.959_964), 0.05);
+        assert_close(normal_p_value(0.0), 1.0);
+
+        // P(X <= 0) = e^-2, P(X >= 6) = 1 - sum(e^-2 * 2^k / k!) for k in 0..6.
+        //
+        assert_close(poisson_p_value(0, 2.0), 2.0 * (-2.0_f64).exp());
+        assert_close(poisson_p_value(6, 2.0), 2.0 * 0.016_563_6);
+        assert_close(poisson_p_value(2, 2.0), 1.0);
+    }
+
+    #[test]
+    fn test_battery_good_generator() {
+        let sample = uniform_from_rng(StdRng::seed_from_u64(1))
+            .take(SAMPLE_SIZE)
+            .collect::<Vec<_>>();
+
+        for result in run_battery(&sample) {
+            assert!(result.passed(), "{:?}", result);
+        }
+
+        let sample = uniform_from_rng(RandGen::seed_from_u64(1))
+            .take(SAMPLE_SIZE)
+            .collect::<Vec<_>>();
+
+        for result in run_battery(&sample) {
+            assert!(result.passed(), "{:?}", result);
+        }
+    }
+
+    #[test]
+    fn test_battery_bad_generators() {
+        let sample = uniform_from_iter(RandGen::new(23, 21321, 21323, 314), 314)
+            .take(SAMPLE_SIZE)
+            .collect::<Vec<_>>();
+
+        assert!(run_battery(&sample).iter().all(|result| !result.passed()));
+
+        // Linearly increasing values are perfectly uniform (the sample covers 8 whole cycles), but not
+        // random at all.
+        //
+        let sample = uniform_from_rng(StepRng::new(0, 1 << 50))
+            .take(1 << 17)
+            .collect::<Vec<_>>();
+
+        let results = run_battery(&sample);
+
+        assert!(results[0].passed(), "{:?}", results[0]);
+        assert!(results[1..].iter().all(|result| !result.passed()));
+    }
+
+    #[test]
+    fn test_battery_constant_sample() {
+        let sample = vec![0.25; SAMPLE_SIZE];
+
+        assert!(run_battery(&sample).iter().all(|result| !result.passed()));
+    }
+}
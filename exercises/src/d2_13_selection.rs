@@ -0,0 +1,287 @@
+use crate::{
+    d2_11_binary_heap::BinaryHeap,
+    d2_3_quicksort::{pivot_3way_with, pivot_by, PivotStrategy},
+    d2_5_sorter::partial_compare,
+    d2_7_introsort::{insertion_sort_by, log2},
+};
+use std::cmp::Ordering;
+
+// Quickselect: like quicksort, but after partitioning, only the partition containing the k-th
+// position is processed, which is O(n) on average.
+//
+// As in the introsort, the number of partitions is bounded (2*log2(n)); when exceeded, the pivots
+// are likely bad, so the remaining subcollection is processed choosing the pivot as the median of
+// medians, which guarantees that each partition discards at least ~30% of the elements, so O(n) in
+// the worst case.
+//
+// After the call, the element at `k` is the one that would be there if the collection was sorted;
+// the elements to its left are not greater, and the ones to its right are not lower.
+
+// Size of the median of medians groups.
+//
+const GROUP_LEN: usize = 5;
+
+pub fn select_nth<T: PartialOrd>(collection: &mut [T], k: usize) -> &T {
+    select_nth_by(collection, k, partial_compare)
+}
+
+pub fn select_nth_by<T, F: Fn(&T, &T) -> Ordering>(
+    collection: &mut [T],
+    k: usize,
+    compare: F,
+) -> &T {
+    assert!(
+        k < collection.len(),
+        "select index {} out of range for length {}",
+        k,
+        collection.len()
+    );
+
+    let max_depth = 2 * log2(collection.len());
+
+    select_rec(collection, k, &compare, max_depth);
+
+    &collection[k]
+}
+
+// The lower median, for collections of even length.
+//
+pub fn median<T: PartialOrd>(collection: &mut [T]) -> Option<&T> {
+    median_by(collection, partial_compare)
+}
+
+pub fn median_by<T, F: Fn(&T, &T) -> Ordering>(collection: &mut [T], compare: F) -> Option<&T> {
+    if collection.is_empty() {
+        None
+    } else {
+        let k = (collection.len() - 1) / 2;
+        Some(select_nth_by(collection, k, compare))
+    }
+}
+
+fn select_rec<T, F: Fn(&T, &T) -> Ordering>(
+    mut collection: &mut [T],
+    mut k: usize,
+    compare: &F,
+    mut max_depth: usize,
+) {
+    loop {
+        if collection.len() <= 1 {
+            return;
+        }
+
+        if max_depth == 0 {
+            median_of_medians_select(collection, k, compare);
+            return;
+        }
+
+        max_depth -= 1;
+
+        let p = pivot_by(&mut *collection, compare);
+
+        match k.cmp(&p) {
+            Ordering::Equal => return,
+            Ordering::Less => collection = &mut collection[..p],
+            Ordering::Greater => {
+                collection = &mut collection[(p + 1)..];
+                k -= p + 1;
+            }
+        }
+    }
+}
+
+// Chooses a pivot index that was already found; used for the median of medians.
+//
+struct FixedPivot(usize);
+
+impl PivotStrategy for FixedPivot {
+    fn choose<T, F: Fn(&T, &T) -> Ordering>(&mut self, _collection: &[T], _compare: &F) -> usize {
+        self.0
+    }
+}
+
+// The three-way partitioning is required for the linear guarantee: with `pivot()`, the values equal
+// to the pivot all end up on the same side, so many duplicates would unbalance the partitions.
+//
+fn median_of_medians_select<T, F: Fn(&T, &T) -> Ordering>(
+    mut collection: &mut [T],
+    mut k: usize,
+    compare: &F,
+) {
+    loop {
+        if collection.len() <= GROUP_LEN {
+            insertion_sort_by(collection, compare);
+            return;
+        }
+
+        let pivot = median_of_medians(collection, compare);
+        let (lt, gt) = pivot_3way_with(&mut *collection, compare, &mut FixedPivot(pivot));
+
+        if k < lt {
+            collection = &mut collection[..lt];
+        } else if k >= gt {
+            collection = &mut collection[gt..];
+            k -= gt;
+        } else {
+            return;
+        }
+    }
+}
+
+// Sorts each group of five elements, and moves its median to the front of the collection; the
+// median of the medians is then selected (recursively) among them.
+//
+fn median_of_medians<T, F: Fn(&T, &T) -> Ordering>(collection: &mut [T], compare: &F) -> usize {
+    let mut medians_len = 0;
+
+    for start in (0..collection.len()).step_by(GROUP_LEN) {
+        let end = (start + GROUP_LEN).min(collection.len());
+
+        insertion_sort_by(&mut collection[start..end], compare);
+        collection.swap(medians_len, start + (end - start - 1) / 2);
+
+        medians_len += 1;
+    }
+
+    let median = (medians_len - 1) / 2;
+
+    median_of_medians_select(&mut collection[..medians_len], median, compare);
+
+    median
+}
+
+// The `k` greatest values of the iterator, in descending order, using O(k) memory: a min heap holds
+// the greatest values found so far, and its root (the lowest of them) is replaced whenever a
+// greater value is found.
+//
+pub fn top_k<T: PartialOrd, I: IntoIterator<Item = T>>(values: I, k: usize) -> Vec<T> {
+    top_k_by(values, k, partial_compare)
+}
+
+pub fn top_k_by<T, I: IntoIterator<Item = T>, F: Fn(&T, &T) -> Ordering>(
+    values: I,
+    k: usize,
+    compare: F,
+) -> Vec<T> {
+    if k == 0 {
+        return Vec::new();
+    }
+
+    let mut heap = BinaryHeap::with_comparator(|a: &T, b: &T| compare(b, a));
+
+    for value in values {
+        if heap.len() < k {
+            heap.push(value);
+        } else if compare(&value, heap.peek().unwrap()) == Ordering::Greater {
+            heap.pop();
+            heap.push(value);
+        }
+    }
+
+    heap.into_sorted_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn random_collection(len: usize, max: u16) -> Vec<u16> {
+        (0..len).map(|_| rand::random::<u16>() % max).collect()
+    }
+
+    fn assert_selected(collection: &[u16], k: usize, sorted: &[u16]) {
+        assert_eq!(collection[k], sorted[k]);
+        assert!(collection[..k].iter().all(|value| *value <= sorted[k]));
+        assert!(collection[(k + 1)..]
+            .iter()
+            .all(|value| *value >= sorted[k]));
+    }
+
+    #[test]
+    fn test_select_nth() {
+        for len in 1..50 {
+            let collection = random_collection(len, 20);
+            let mut sorted = collection.clone();
+            sorted.sort();
+
+            for k in 0..len {
+                let mut collection = collection.clone();
+                assert_eq!(*select_nth(&mut collection, k), sorted[k]);
+                assert_selected(&collection, k, &sorted);
+            }
+        }
+    }
+
+    #[test]
+    fn test_select_nth_by() {
+        let mut collection = vec![3, 1, 4, 1, 5, 9, 2, 6];
+
+        assert_eq!(*select_nth_by(&mut collection, 0, |a, b| b.cmp(a)), 9);
+        assert_eq!(*select_nth_by(&mut collection, 7, |a, b| b.cmp(a)), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "out of range")]
+    fn test_select_nth_out_of_range() {
+        select_nth(&mut [1, 2, 3], 3);
+    }
+
+    // Forces the median of medians fallback.
+    //
+    #[test]
+    fn test_select_median_of_medians() {
+        for &max in &[3, 1000, u16::MAX] {
+            let collection = random_collection(1000, max);
+            let mut sorted = collection.clone();
+            sorted.sort();
+
+            for &k in &[0, 1, 333, 500, 998, 999] {
+                let mut collection = collection.clone();
+                select_rec(&mut collection, k, &partial_compare, 0);
+                assert_selected(&collection, k, &sorted);
+            }
+        }
+    }
+
+    // Same as the introsort: with `pivot()`, all-equal values are the worst case.
+    //
+    #[test]
+    fn test_select_all_equal() {
+        let mut collection = vec![7; 1_000_000];
+
+        assert_eq!(*select_nth(&mut collection, 500_000), 7);
+    }
+
+    #[test]
+    fn test_median() {
+        assert_eq!(median::<u8>(&mut []), None);
+        assert_eq!(median(&mut [5]), Some(&5));
+        assert_eq!(median(&mut [4, 1, 3, 2]), Some(&2));
+        assert_eq!(median(&mut [5, 1, 4, 2, 3]), Some(&3));
+        assert_eq!(median_by(&mut [5, 1, 4, 2, 3], |a, b| b.cmp(a)), Some(&3));
+    }
+
+    #[test]
+    fn test_top_k() {
+        let collection = random_collection(1000, 100);
+        let mut sorted = collection.clone();
+        sorted.sort_by(|a, b| b.cmp(a));
+
+        for &k in &[0, 1, 10, 999, 1000, 2000] {
+            let expected = &sorted[..k.min(sorted.len())];
+            assert_eq!(top_k(collection.iter().copied(), k), expected);
+        }
+
+        assert_eq!(top_k_by(1..=10, 3, |a, b| b.cmp(a)), vec![1, 2, 3]);
+    }
+
+    // Only the iterator is required, not a collection; 7919 is coprime with 10^6, so this is a
+    // permutation of 0..10^6.
+    //
+    #[test]
+    fn test_top_k_stream() {
+        let top = top_k((0..1_000_000_u64).map(|i| i * 7_919 % 1_000_000), 3);
+
+        assert_eq!(top, vec![999_999, 999_998, 999_997]);
+    }
+}
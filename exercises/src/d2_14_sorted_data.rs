@@ -0,0 +1,303 @@
+use crate::{d2_11_binary_heap::BinaryHeap, d2_5_sorter::partial_compare};
+use std::{cmp::Ordering, ops::Range};
+
+// K-way merge: lazily merges any number of sorted iterators into a single sorted one.
+//
+// The current element of each source is kept in a heap (a min heap, via the inverted comparator),
+// together with the source index; the lowest is returned, and replaced with the next element of
+// its source. On equal elements, the lower source index comes first, so the merge is stable by
+// source order. Each element costs O(log(k)) comparisons, and only one element per source is held
+// in memory.
+//
+pub fn kmerge<S, I>(sources: S) -> KMerge<I::IntoIter, impl EntryCompare<I::Item>>
+where
+    S: IntoIterator<Item = I>,
+    I: IntoIterator,
+    I::Item: PartialOrd,
+{
+    kmerge_by(sources, partial_compare)
+}
+
+pub fn kmerge_by<S, I, F>(sources: S, compare: F) -> KMerge<I::IntoIter, impl EntryCompare<I::Item>>
+where
+    S: IntoIterator<Item = I>,
+    I: IntoIterator,
+    F: Fn(&I::Item, &I::Item) -> Ordering,
+{
+    let mut sources = sources
+        .into_iter()
+        .map(IntoIterator::into_iter)
+        .collect::<Vec<_>>();
+
+    let mut heap = BinaryHeap::with_comparator(
+        move |(value_1, source_1): &Entry<I::Item>, (value_2, source_2): &Entry<I::Item>| {
+            compare(value_2, value_1).then(source_2.cmp(source_1))
+        },
+    );
+
+    for (source_index, source) in sources.iter_mut().enumerate() {
+        if let Some(value) = source.next() {
+            heap.push((value, source_index));
+        }
+    }
+
+    KMerge { sources, heap }
+}
+
+// A heap entry: the current value of a source, and the source index.
+//
+pub type Entry<T> = (T, usize);
+
+// The heap comparator, which is built by `kmerge_by()` from the values comparator.
+//
+pub trait EntryCompare<T>: Fn(&Entry<T>, &Entry<T>) -> Ordering {}
+
+impl<T, F: Fn(&Entry<T>, &Entry<T>) -> Ordering> EntryCompare<T> for F {}
+
+pub struct KMerge<I: Iterator, F> {
+    sources: Vec<I>,
+    heap: BinaryHeap<Entry<I::Item>, F>,
+}
+
+impl<I: Iterator, F: EntryCompare<I::Item>> Iterator for KMerge<I, F> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (value, source_index) = self.heap.pop()?;
+
+        if let Some(next_value) = self.sources[source_index].next() {
+            self.heap.push((next_value, source_index));
+        }
+
+        Some(value)
+    }
+
+    // The heap holds at most one element per source; the sources may have more.
+    //
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (mut low, mut high) = (self.heap.len(), Some(self.heap.len()));
+
+        for source in &self.sources {
+            let (source_low, source_high) = source.size_hint();
+            low = low.saturating_add(source_low);
+            high = high.zip(source_high).and_then(|(a, b)| a.checked_add(b));
+        }
+
+        (low, high)
+    }
+}
+
+// Binary searches on sorted collections. As in the C++ standard library:
+//
+// - `lower_bound()`: index of the first element not lower than the value;
+// - `upper_bound()`: index of the first element greater than the value;
+// - `equal_range()`: the range of the elements equal to the value (empty, at the insertion position,
+//   if there are none).
+//
+// All the indexes are valid insertion positions, so they're in `[0, len]`.
+
+pub fn lower_bound<T: PartialOrd>(collection: &[T], value: &T) -> usize {
+    lower_bound_by(collection, value, partial_compare)
+}
+
+pub fn lower_bound_by<T, F: Fn(&T, &T) -> Ordering>(
+    collection: &[T],
+    value: &T,
+    compare: F,
+) -> usize {
+    collection.partition_point(|element| compare(element, value) == Ordering::Less)
+}
+
+pub fn upper_bound<T: PartialOrd>(collection: &[T], value: &T) -> usize {
+    upper_bound_by(collection, value, partial_compare)
+}
+
+pub fn upper_bound_by<T, F: Fn(&T, &T) -> Ordering>(
+    collection: &[T],
+    value: &T,
+    compare: F,
+) -> usize {
+    collection.partition_point(|element| compare(element, value) != Ordering::Greater)
+}
+
+pub fn equal_range<T: PartialOrd>(collection: &[T], value: &T) -> Range<usize> {
+    equal_range_by(collection, value, partial_compare)
+}
+
+pub fn equal_range_by<T, F: Fn(&T, &T) -> Ordering>(
+    collection: &[T],
+    value: &T,
+    compare: F,
+) -> Range<usize> {
+    let start = lower_bound_by(collection, value, &compare);
+    let end = start + upper_bound_by(&collection[start..], value, &compare);
+
+    start..end
+}
+
+// Same as `slice::partition_point()`, but the bound is found by doubling the tested position, then
+// searched in binary fashion; this is O(log(p)), where `p` is the result, so when the prefix is
+// short, it's faster than a plain binary search.
+//
+pub fn gallop<T, P: Fn(&T) -> bool>(collection: &[T], predicate: P) -> usize {
+    let mut bound = 1;
+
+    while bound <= collection.len() && predicate(&collection[bound - 1]) {
+        bound *= 2;
+    }
+
+    // The prefix length is in `[bound / 2, min(bound, len)]`.
+    //
+    let (mut low, mut high) = (bound / 2, bound.min(collection.len() + 1) - 1);
+
+    while low < high {
+        let middle = low + (high - low).div_ceil(2);
+
+        if predicate(&collection[middle - 1]) {
+            low = middle;
+        } else {
+            high = middle - 1;
+        }
+    }
+
+    low
+}
+
+// Galloping versions of the bounds, for values expected near the start of the collection (e.g.
+// when repeatedly searching the remainder of a collection, as in the merges).
+//
+pub fn gallop_lower_bound<T: PartialOrd>(collection: &[T], value: &T) -> usize {
+    gallop_lower_bound_by(collection, value, partial_compare)
+}
+
+pub fn gallop_lower_bound_by<T, F: Fn(&T, &T) -> Ordering>(
+    collection: &[T],
+    value: &T,
+    compare: F,
+) -> usize {
+    gallop(collection, |element| {
+        compare(element, value) == Ordering::Less
+    })
+}
+
+pub fn gallop_upper_bound<T: PartialOrd>(collection: &[T], value: &T) -> usize {
+    gallop_upper_bound_by(collection, value, partial_compare)
+}
+
+pub fn gallop_upper_bound_by<T, F: Fn(&T, &T) -> Ordering>(
+    collection: &[T],
+    value: &T,
+    compare: F,
+) -> usize {
+    gallop(collection, |element| {
+        compare(element, value) != Ordering::Greater
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kmerge() {
+        let merged =
+            kmerge(vec![vec![1, 4, 7], vec![], vec![2, 5, 8, 9], vec![3, 6]]).collect::<Vec<_>>();
+
+        assert_eq!(merged, (1..=9).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_kmerge_random() {
+        let sources = (0..20)
+            .map(|_| {
+                let mut source = (0..rand::random::<u8>() % 50)
+                    .map(|_| rand::random::<u16>())
+                    .collect::<Vec<_>>();
+                source.sort();
+                source
+            })
+            .collect::<Vec<_>>();
+
+        let mut expected = sources.concat();
+        expected.sort();
+
+        let merged = kmerge(sources.iter().map(|source| source.iter().copied()));
+
+        assert_eq!(merged.size_hint(), (expected.len(), Some(expected.len())));
+        assert_eq!(merged.collect::<Vec<_>>(), expected);
+    }
+
+    // Equal keys come out in source order, then in order within each source.
+    //
+    #[test]
+    fn test_kmerge_stable() {
+        let sources = vec![
+            vec![(1, 'a'), (2, 'b'), (2, 'c')],
+            vec![(1, 'd'), (2, 'e')],
+            vec![(0, 'f'), (2, 'g')],
+        ];
+
+        let merged = kmerge_by(sources, |a, b| a.0.cmp(&b.0))
+            .map(|(_, id)| id)
+            .collect::<String>();
+
+        assert_eq!(merged, "fadbceg");
+    }
+
+    // Only one element per source is pulled at a time, so infinite sources work.
+    //
+    #[test]
+    fn test_kmerge_infinite() {
+        let merged = kmerge(vec![(0..).step_by(3), (1..).step_by(3), (2..).step_by(3)])
+            .take(100)
+            .collect::<Vec<_>>();
+
+        assert_eq!(merged, (0..100).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_bounds() {
+        let collection = [1, 2, 2, 2, 5, 7, 7];
+
+        for value in 0..=8 {
+            let expected_lower = collection.iter().filter(|x| **x < value).count();
+            let expected_upper = collection.iter().filter(|x| **x <= value).count();
+
+            assert_eq!(lower_bound(&collection, &value), expected_lower);
+            assert_eq!(upper_bound(&collection, &value), expected_upper);
+            assert_eq!(
+                equal_range(&collection, &value),
+                expected_lower..expected_upper
+            );
+            assert_eq!(gallop_lower_bound(&collection, &value), expected_lower);
+            assert_eq!(gallop_upper_bound(&collection, &value), expected_upper);
+        }
+
+        assert_eq!(lower_bound(&[], &1), 0);
+        assert_eq!(equal_range(&[], &1), 0..0);
+    }
+
+    #[test]
+    fn test_bounds_by() {
+        let collection = [9, 7, 7, 3];
+        let descending = |a: &i32, b: &i32| b.cmp(a);
+
+        assert_eq!(lower_bound_by(&collection, &7, descending), 1);
+        assert_eq!(upper_bound_by(&collection, &7, descending), 3);
+        assert_eq!(equal_range_by(&collection, &7, descending), 1..3);
+        assert_eq!(equal_range_by(&collection, &5, descending), 3..3);
+        assert_eq!(gallop_lower_bound_by(&collection, &7, descending), 1);
+        assert_eq!(gallop_upper_bound_by(&collection, &7, descending), 3);
+    }
+
+    #[test]
+    fn test_gallop() {
+        let collection = (0..100).collect::<Vec<_>>();
+
+        for limit in 0..=100 {
+            assert_eq!(gallop(&collection, |value| *value < limit), limit);
+        }
+
+        assert_eq!(gallop(&[] as &[i32], |_| true), 0);
+    }
+}
@@ -0,0 +1,458 @@
+use rand::Rng;
+use rand_core::RngCore;
+use std::{collections::HashSet, fmt};
+
+// Random permutations and samples. All the functions take any `RngCore`, so they can be driven by
+// `RandGen` (see `d2_3`), `BigGen` (material), or the `rand` generators; with a seeded generator,
+// the results are reproducible.
+
+// Fisher-Yates (Durstenfeld) shuffle: each position, from the last, is swapped with a random one
+// among the positions not fixed yet (including itself), so that all the permutations are equally
+// likely. O(n).
+//
+pub fn shuffle<T, R: RngCore + ?Sized>(collection: &mut [T], rng: &mut R) {
+    for i in (1..collection.len()).rev() {
+        let j = rng.gen_range(0..=i);
+        collection.swap(i, j);
+    }
+}
+
+// Shuffles only the first `amount` positions, which are filled with a uniform sample of the whole
+// collection, in random order; the remaining elements are left in an unspecified order. Returns the
+// (sample, rest) subcollections. O(amount).
+//
+pub fn partial_shuffle<'a, T, R: RngCore + ?Sized>(
+    collection: &'a mut [T],
+    amount: usize,
+    rng: &mut R,
+) -> (&'a mut [T], &'a mut [T]) {
+    assert!(
+        amount <= collection.len(),
+        "shuffle amount {} out of range for length {}",
+        amount,
+        collection.len()
+    );
+
+    for i in 0..amount {
+        let j = rng.gen_range(i..collection.len());
+        collection.swap(i, j);
+    }
+
+    collection.split_at_mut(amount)
+}
+
+// Reservoir sampling (Algorithm R): a uniform sample of `k` elements of an iterator of unknown
+// length, in a single pass, using O(k) memory. The i-th element (from 0) replaces a random element
+// of the reservoir with probability k/(i+1).
+//
+// If the iterator has fewer than `k` elements, they're all returned. The order of the sample is not
+// random; shuffle it, if required.
+//
+pub fn reservoir_sample<T, I: IntoIterator<Item = T>, R: RngCore + ?Sized>(
+    values: I,
+    k: usize,
+    rng: &mut R,
+) -> Vec<T> {
+    let mut reservoir = Vec::with_capacity(k);
+
+    for (i, value) in values.into_iter().enumerate() {
+        if i < k {
+            reservoir.push(value);
+        } else {
+            let j = rng.gen_range(0..=i);
+
+            if j < k {
+                reservoir[j] = value;
+            }
+        }
+    }
+
+    reservoir
+}
+
+// Floyd's algorithm: a uniform random subset of `k` indices in `0..len`, in O(k) time and memory,
+// regardless of `len`. For each `j` in `len-k..len`, a random index in `0..=j` is taken, or `j`
+// itself, if the index has already been taken.
+//
+// The indices are returned in increasing order.
+//
+pub fn subset_indices<R: RngCore + ?Sized>(len: usize, k: usize, rng: &mut R) -> Vec<usize> {
+    assert!(
+        k <= len,
+        "subset size {} out of range for length {}",
+        k,
+        len
+    );
+
+    let mut taken = HashSet::with_capacity(k);
+
+    for j in (len - k)..len {
+        let index = rng.gen_range(0..=j);
+
+        if !taken.insert(index) {
+            taken.insert(j);
+        }
+    }
+
+    let mut indices = taken.into_iter().collect::<Vec<_>>();
+    indices.sort_unstable();
+    indices
+}
+
+// The elements of a random k-subset, in their original order.
+//
+pub fn random_subset<'a, T, R: RngCore + ?Sized>(
+    collection: &'a [T],
+    k: usize,
+    rng: &mut R,
+) -> Vec<&'a T> {
+    subset_indices(collection.len(), k, rng)
+        .into_iter()
+        .map(|index| &collection[index])
+        .collect()
+}
+
+#[derive(Debug, PartialEq)]
+pub enum WeightsError {
+    Empty,
+    // Negative, NaN or infinite weight, at the given index.
+    //
+    Invalid(usize),
+    ZeroTotal,
+}
+
+impl fmt::Display for WeightsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            WeightsError::Empty => write!(f, "No weights"),
+            WeightsError::Invalid(index) => write!(f, "Invalid weight at index {}", index),
+            WeightsError::ZeroTotal => write!(f, "The weights sum to zero"),
+        }
+    }
+}
+
+impl std::error::Error for WeightsError {}
+
+// Alias method (Vose's version): samples index `i` with probability `weights[i] / sum(weights)`, in
+// O(1) per sample, after an O(n) setup.
+//
+// The weights are scaled so that their average is 1, and each one is put in a column of height 1;
+// the columns of the weights lower than 1 are topped up with (a part of) a weight greater than 1,
+// its "alias". Sampling picks a random column, then either the column index or its alias, according
+// to the column probability.
+//
+pub struct AliasTable {
+    probabilities: Vec<f64>,
+    aliases: Vec<usize>,
+}
+
+impl AliasTable {
+    pub fn new(weights: &[f64]) -> Result<Self, WeightsError> {
+        if weights.is_empty() {
+            return Err(WeightsError::Empty);
+        }
+
+        if let Some(index) = weights
+            .iter()
+            .position(|weight| !weight.is_finite() || *weight < 0.0)
+        {
+            return Err(WeightsError::Invalid(index));
+        }
+
+        // Finite weights may still overflow when summed (or multiplied by the length), so they're
+        // scaled to the largest one first, and divided by the total before being multiplied.
+        //
+        let max_weight = weights.iter().copied().fold(0.0, f64::max);
+
+        if max_weight == 0.0 {
+            return Err(WeightsError::ZeroTotal);
+        }
+
+        // Each scaled weight is at most 1, so the total is at most the length.
+        //
+        let total = weights
+            .iter()
+            .map(|weight| weight / max_weight)
+            .sum::<f64>();
+
+        let len = weights.len();
+        let mut probabilities = weights
+            .iter()
+            .map(|weight| weight / max_weight / total * len as f64)
+            .collect::<Vec<_>>();
+        let mut aliases = (0..len).collect::<Vec<_>>();
+
+        let (mut small, mut large): (Vec<usize>, Vec<usize>) =
+            (0..len).partition(|i| probabilities[*i] < 1.0);
+
+        while let (Some(&s), Some(&l)) = (small.last(), large.last()) {
+            small.pop();
+            aliases[s] = l;
+            probabilities[l] -= 1.0 - probabilities[s];
+
+            if probabilities[l] < 1.0 {
+                large.pop();
+                small.push(l);
+            }
+        }
+
+        // What's left is 1, except for rounding errors.
+        //
+        for i in small.into_iter().chain(large) {
+            probabilities[i] = 1.0;
+        }
+
+        Ok(AliasTable {
+            probabilities,
+            aliases,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.probabilities.len()
+    }
+
+    // Always false, since the table can't be built without weights.
+    //
+    pub fn is_empty(&self) -> bool {
+        self.probabilities.is_empty()
+    }
+
+    pub fn sample<R: RngCore + ?Sized>(&self, rng: &mut R) -> usize {
+        let column = rng.gen_range(0..self.len());
+
+        if rng.gen::<f64>() < self.probabilities[column] {
+            column
+        } else {
+            self.aliases[column]
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        d2_12_random_quality::{chi_square_p_value, ALPHA},
+        d2_3_quicksort::RandGen,
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+
+    // Chi-square test of the observed counts against the expected ones (see `d2_12`).
+    //
+    fn assert_distribution(counts: &[usize], expected: &[f64]) {
+        let statistic = counts
+            .iter()
+            .zip(expected)
+            .map(|(count, expected)| (*count as f64 - expected).powi(2) / expected)
+            .sum::<f64>();
+        let p_value = chi_square_p_value(statistic, counts.len() - 1);
+
+        assert!(p_value >= ALPHA, "p = {} for {:?}", p_value, counts);
+    }
+
+    fn permutation_index(permutation: &[usize]) -> usize {
+        permutation.iter().fold(0, |index, value| index * 4 + value)
+    }
+
+    #[test]
+    fn test_shuffle_uniform() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let mut counts = vec![0; 256];
+
+        for _ in 0..24_000 {
+            let mut collection = vec![0, 1, 2, 3];
+            shuffle(&mut collection, &mut rng);
+            counts[permutation_index(&collection)] += 1;
+        }
+
+        let counts = counts
+            .into_iter()
+            .filter(|count| *count > 0)
+            .collect::<Vec<_>>();
+
+        assert_eq!(counts.len(), 24);
+        assert_distribution(&counts, &[1000.0; 24]);
+    }
+
+    #[test]
+    fn test_shuffle_generators() {
+        let mut collection = (0..100).collect::<Vec<_>>();
+
+        shuffle(&mut collection, &mut RandGen::seed_from_u64(3));
+        shuffle(&mut collection, &mut rand::thread_rng());
+        shuffle(&mut [] as &mut [i32], &mut rand::thread_rng());
+
+        let mut sorted = collection.clone();
+        sorted.sort();
+
+        assert_ne!(collection, sorted);
+        assert_eq!(sorted, (0..100).collect::<Vec<_>>());
+
+        // Reproducible with the same seed.
+        //
+        let mut collection_1 = (0..100).collect::<Vec<_>>();
+        let mut collection_2 = collection_1.clone();
+
+        shuffle(&mut collection_1, &mut RandGen::seed_from_u64(7));
+        shuffle(&mut collection_2, &mut RandGen::seed_from_u64(7));
+
+        assert_eq!(collection_1, collection_2);
+    }
+
+    #[test]
+    fn test_partial_shuffle() {
+        let mut rng = StdRng::seed_from_u64(2);
+        let mut counts = vec![0; 10];
+
+        for _ in 0..10_000 {
+            let mut collection = (0..10).collect::<Vec<_>>();
+            let (sample, rest) = partial_shuffle(&mut collection, 3, &mut rng);
+
+            assert_eq!((sample.len(), rest.len()), (3, 7));
+
+            for value in sample.iter() {
+                counts[*value] += 1;
+            }
+
+            collection.sort();
+            assert_eq!(collection, (0..10).collect::<Vec<_>>());
+        }
+
+        assert_distribution(&counts, &[3000.0; 10]);
+    }
+
+    #[test]
+    #[should_panic(expected = "out of range")]
+    fn test_partial_shuffle_out_of_range() {
+        partial_shuffle(&mut [1, 2], 3, &mut rand::thread_rng());
+    }
+
+    #[test]
+    fn test_reservoir_sample() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let mut counts = vec![0; 20];
+
+        for _ in 0..10_000 {
+            for value in reservoir_sample(0..20, 5, &mut rng) {
+                counts[value] += 1;
+            }
+        }
+
+        assert_distribution(&counts, &[2500.0; 20]);
+
+        assert_eq!(reservoir_sample(0..3, 5, &mut rng), vec![0, 1, 2]);
+        assert!(reservoir_sample(0..3, 0, &mut rng).is_empty());
+    }
+
+    #[test]
+    fn test_subset() {
+        let mut rng = StdRng::seed_from_u64(4);
+        let mut counts = vec![0; 20];
+
+        for _ in 0..10_000 {
+            let indices = subset_indices(20, 5, &mut rng);
+
+            assert_eq!(indices.len(), 5);
+            assert!(indices.windows(2).all(|pair| pair[0] < pair[1]));
+
+            for index in indices {
+                counts[index] += 1;
+            }
+        }
+
+        assert_distribution(&counts, &[2500.0; 20]);
+
+        let collection = ['a', 'b', 'c', 'd'];
+
+        assert_eq!(
+            random_subset(&collection, 4, &mut rng),
+            vec![&'a', &'b', &'c', &'d']
+        );
+        assert!(random_subset(&collection, 0, &mut rng).is_empty());
+
+        // Huge lengths don't require memory.
+        //
+        assert_eq!(subset_indices(usize::MAX, 3, &mut rng).len(), 3);
+    }
+
+    #[test]
+    fn test_alias_table() {
+        let weights = [1.0, 0.0, 3.0, 6.0, 10.0];
+        let table = AliasTable::new(&weights).unwrap();
+        let mut rng = StdRng::seed_from_u64(5);
+        let mut counts = vec![0; weights.len()];
+
+        for _ in 0..100_000 {
+            counts[table.sample(&mut rng)] += 1;
+        }
+
+        assert_eq!(counts[1], 0);
+
+        // The zero weight is excluded.
+        //
+        let (counts, expected): (Vec<_>, Vec<_>) = counts
+            .into_iter()
+            .zip(weights.iter())
+            .filter(|(_, weight)| **weight > 0.0)
+            .map(|(count, weight)| (count, weight * 5_000.0))
+            .unzip();
+
+        assert_distribution(&counts, &expected);
+    }
+
+    #[test]
+    fn test_alias_table_errors() {
+        assert_eq!(AliasTable::new(&[]).err(), Some(WeightsError::Empty));
+        assert_eq!(
+            AliasTable::new(&[1.0, -1.0]).err(),
+            Some(WeightsError::Invalid(1))
+        );
+        assert_eq!(
+            AliasTable::new(&[f64::NAN]).err(),
+            Some(WeightsError::Invalid(0))
+        );
+        assert_eq!(
+            AliasTable::new(&[0.0, 0.0]).err(),
+            Some(WeightsError::ZeroTotal)
+        );
+
+        let table = AliasTable::new(&[2.0]).unwrap();
+
+        assert_eq!(table.sample(&mut RandGen::seed_from_u64(1)), 0);
+        assert_eq!(table.len(), 1);
+    }
+
+    // The sum of these weights, and their product by the length, overflow if computed directly.
+    //
+    #[test]
+    fn test_alias_table_large_weights() {
+        let weights = [f64::MAX, 1e308, 1e308, 1.0];
+        let table = AliasTable::new(&weights).unwrap();
+        let mut rng = StdRng::seed_from_u64(6);
+        let mut counts = vec![0; weights.len()];
+
+        for _ in 0..100_000 {
+            counts[table.sample(&mut rng)] += 1;
+        }
+
+        assert_eq!(counts[3], 0);
+
+        let total = f64::MAX / 1e308 + 2.0;
+        let expected = [f64::MAX / 1e308, 1.0, 1.0]
+            .iter()
+            .map(|weight| weight / total * 100_000.0)
+            .collect::<Vec<_>>();
+
+        assert_distribution(&counts[..3], &expected);
+
+        let table = AliasTable::new(&[1e308, 1e308, 1.0]).unwrap();
+        let mut counts = [0; 3];
+
+        for _ in 0..100_000 {
+            counts[table.sample(&mut rng)] += 1;
+        }
+
+        assert_distribution(&counts[..2], &[50_000.0; 2]);
+    }
+}
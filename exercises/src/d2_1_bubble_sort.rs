@@ -1,6 +1,25 @@
+use crate::{d2_5_sorter::partial_compare, d2_6_instrumented_sorts::SortObserver};
+use std::cmp::Ordering;
+
 // "Bubble" the highest element for each cycle to the last position(s), by swapping adjacent pairs.
 //
 pub fn bubble_sort<T: PartialOrd>(collection: &mut [T]) {
+    bubble_sort_by(collection, partial_compare)
+}
+
+pub fn bubble_sort_by<T, F: Fn(&T, &T) -> Ordering>(collection: &mut [T], compare: F) {
+    bubble_sort_observed(collection, compare, &mut ())
+}
+
+// The operations are reported to the observer (see `d2_6`).
+//
+pub fn bubble_sort_observed<T, F: Fn(&T, &T) -> Ordering, O: SortObserver<T>>(
+    collection: &mut [T],
+    compare: F,
+    observer: &mut O,
+) {
+    observer.enter(0, collection.len());
+
     // If the collection is already sorted, only one pass is performed.
     //
     for start in 0..collection.len() {
@@ -10,22 +29,41 @@ pub fn bubble_sort<T: PartialOrd>(collection: &mut [T]) {
         // highest element [considered] to the end of the [considered] interval.
         //
         for i in 0..(collection.len() - 1 - start) {
-            if collection[i] > collection[i + 1] {
+            observer.compare(i, i + 1);
+
+            if compare(&collection[i], &collection[i + 1]) == Ordering::Greater {
+                observer.swap(i, i + 1);
                 collection.swap(i, i + 1);
                 sorted = false;
             }
         }
 
         if sorted {
-            return;
+            break;
         }
     }
+
+    observer.exit();
 }
 
 #[cfg(test)]
 mod tests {
-    use super::bubble_sort;
-    use crate::test_sort;
+    use super::{bubble_sort, bubble_sort_by};
+    use crate::{test_sort, test_sort_properties};
 
     test_sort!(test_bubble_sort, collection, bubble_sort(&mut collection));
+
+    test_sort!(
+        test_bubble_sort_by,
+        collection,
+        bubble_sort_by(&mut collection, |a, b| a.cmp(b))
+    );
+
+    test_sort_properties!(
+        test_bubble_sort_properties,
+        collection,
+        bubble_sort(&mut collection),
+        stable = true,
+        huge_len = 1_000
+    );
 }
@@ -1,3 +1,6 @@
+use crate::{d2_5_sorter::partial_compare, d2_6_instrumented_sorts::SortObserver};
+use std::cmp::Ordering;
+
 // Recursively sort the two subcollections (until there is only one element), then sort them by
 // iterating both at the same time, and comparing the elements.
 //
@@ -6,45 +9,193 @@
 // - it doesn't mutate the source collection;
 // - it's simpler, and uses the APIs better;
 // - it borrows the source;
-// - it has less allocations (conversions).
+// - it has less allocations (conversions);
+// - it's stable, and only requires `Clone`.
+//
+pub fn merge_sort_improved<T: PartialOrd + Clone>(collection: &[T]) -> Vec<T> {
+    merge_sort_improved_by(collection, partial_compare)
+}
+
+pub fn merge_sort_improved_by<T: Clone, F: Fn(&T, &T) -> Ordering>(
+    collection: &[T],
+    compare: F,
+) -> Vec<T> {
+    merge_sort_improved_observed(collection, compare, &mut ())
+}
+
+// The operations are reported to the observer (see `d2_6`).
 //
-pub fn merge_sort_improved<T: PartialOrd + Copy>(collection: &[T]) -> Vec<T> {
-    if collection.len() == 1 {
+pub fn merge_sort_improved_observed<T: Clone, F: Fn(&T, &T) -> Ordering, O: SortObserver<T>>(
+    collection: &[T],
+    compare: F,
+    observer: &mut O,
+) -> Vec<T> {
+    merge_sort_improved_rec(collection, 0, &compare, observer)
+}
+
+// The comparator is passed by reference, otherwise, each recursion level would instantiate the
+// function with an additional reference level (`&F`, `&&F`...).
+//
+// `offset` is the position of the subcollection in the whole collection, which the observer uses.
+//
+fn merge_sort_improved_rec<T: Clone, F: Fn(&T, &T) -> Ordering, O: SortObserver<T>>(
+    collection: &[T],
+    offset: usize,
+    compare: &F,
+    observer: &mut O,
+) -> Vec<T> {
+    observer.enter(offset, offset + collection.len());
+
+    // The empty collection needs to be included, otherwise, it's split (and recursed) forever.
+    //
+    // The elements are already in place, so they're not reported as writes.
+    //
+    if collection.len() <= 1 {
+        observer.allocation();
+        observer.exit();
         return collection.to_vec();
     }
 
-    let (collection_1, collection_2) = collection.split_at(collection.len() / 2);
+    let middle = collection.len() / 2;
+    let (collection_1, collection_2) = collection.split_at(middle);
 
-    let collection_1 = merge_sort_improved(collection_1);
-    let collection_2 = merge_sort_improved(collection_2);
+    let collection_1 = merge_sort_improved_rec(collection_1, offset, compare, observer);
+    let collection_2 = merge_sort_improved_rec(collection_2, offset + middle, compare, observer);
 
+    observer.allocation();
     let mut sorted_collection: Vec<T> = Vec::with_capacity(collection.len());
 
-    let mut collection_1_iter = collection_1.into_iter().peekable();
-    let mut collection_2_iter = collection_2.into_iter().peekable();
+    let (mut i_1, mut i_2) = (0, 0);
 
-    while let (Some(entry_1), Some(entry_2)) = (collection_1_iter.peek(), collection_2_iter.peek())
-    {
-        if entry_1 < entry_2 {
-            sorted_collection.push(collection_1_iter.next().unwrap());
+    // On equal elements, the left one is taken first, which makes the sort stable.
+    //
+    while i_1 < collection_1.len() && i_2 < collection_2.len() {
+        observer.compare(offset + middle + i_2, offset + i_1);
+
+        let value = if compare(&collection_2[i_2], &collection_1[i_1]) == Ordering::Less {
+            i_2 += 1;
+            collection_2[i_2 - 1].clone()
         } else {
-            sorted_collection.push(collection_2_iter.next().unwrap());
-        }
+            i_1 += 1;
+            collection_1[i_1 - 1].clone()
+        };
+
+        observer.write(offset + sorted_collection.len(), &value);
+        sorted_collection.push(value);
     }
 
     // One of the collections still has elements at this point.
     //
-    sorted_collection.extend(collection_1_iter);
-    sorted_collection.extend(collection_2_iter);
+    for value in collection_1[i_1..].iter().chain(&collection_2[i_2..]) {
+        observer.write(offset + sorted_collection.len(), value);
+        sorted_collection.push(value.clone());
+    }
+
+    observer.exit();
 
     sorted_collection
 }
 
+// In-place (API-wise), stable merge sort, which works with any type.
+//
+// Elements can't be moved out of a slice without either copying them (Copy/Clone) or unsafe code, so
+// the positions of the elements are sorted instead, then the collection is rearranged accordingly.
+// A single buffer is allocated, which holds both the positions and the scratch area used for merging
+// them, and is reused at each recursion level.
+//
+// On equal elements, the ones in the left subcollection are taken first, which makes the sort stable;
+// this allows multi-key sorting, by sorting on each key, from the least significant.
+//
+pub fn merge_sort_stable<T: PartialOrd>(collection: &mut [T]) {
+    merge_sort_stable_by(collection, partial_compare)
+}
+
+pub fn merge_sort_stable_by<T, F: Fn(&T, &T) -> Ordering>(collection: &mut [T], compare: F) {
+    let len = collection.len();
+
+    if len <= 1 {
+        return;
+    }
+
+    let mut buffer = (0..len).chain(0..len).collect::<Vec<usize>>();
+    let (positions, scratch) = buffer.split_at_mut(len);
+
+    merge_sort_positions(positions, scratch, &|i: &usize, j: &usize| {
+        compare(&collection[*i], &collection[*j])
+    });
+
+    apply_permutation(collection, positions);
+}
+
+fn merge_sort_positions<F: Fn(&usize, &usize) -> Ordering>(
+    positions: &mut [usize],
+    scratch: &mut [usize],
+    compare: &F,
+) {
+    if positions.len() <= 1 {
+        return;
+    }
+
+    let middle = positions.len() / 2;
+
+    {
+        let (positions_1, positions_2) = positions.split_at_mut(middle);
+        let (scratch_1, scratch_2) = scratch.split_at_mut(middle);
+
+        merge_sort_positions(positions_1, scratch_1, compare);
+        merge_sort_positions(positions_2, scratch_2, compare);
+    }
+
+    let (mut i_1, mut i_2) = (0, middle);
+
+    for destination in scratch.iter_mut() {
+        // The right element is taken only if strictly lower, for stability.
+        //
+        let take_right = i_1 == middle
+            || (i_2 < positions.len()
+                && compare(&positions[i_2], &positions[i_1]) == Ordering::Less);
+
+        if take_right {
+            *destination = positions[i_2];
+            i_2 += 1;
+        } else {
+            *destination = positions[i_1];
+            i_1 += 1;
+        }
+    }
+
+    positions.copy_from_slice(scratch);
+}
+
+// Moves the element at `positions[i]` to `i`, by following the permutation cycles, and swapping the
+// elements along each of them; `positions` is used to mark the positions already processed.
+//
+fn apply_permutation<T>(collection: &mut [T], positions: &mut [usize]) {
+    for start in 0..collection.len() {
+        let mut current = start;
+
+        while positions[current] != current {
+            let source = positions[current];
+            positions[current] = current;
+
+            // When the cycle closes, the element originally at `start` has already been carried to
+            // `current` by the previous swaps.
+            //
+            if source != start {
+                collection.swap(current, source);
+                current = source;
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::merge_sort_improved;
+    use super::{
+        merge_sort_improved, merge_sort_improved_by, merge_sort_stable, merge_sort_stable_by,
+    };
     use crate::d2_2_merge_sort_source::source_merge_sort;
-    use crate::test_sort;
+    use crate::{test_sort, test_sort_properties};
 
     test_sort!(
         test_source_merge_sort,
@@ -57,4 +208,120 @@ mod tests {
         collection,
         collection = merge_sort_improved(&collection)
     );
+
+    test_sort!(
+        test_merge_sort_immutable_by,
+        collection,
+        collection = merge_sort_improved_by(&collection, |a, b| a.cmp(b))
+    );
+
+    #[test]
+    fn test_merge_sort_immutable_empty() {
+        assert_eq!(merge_sort_improved::<i32>(&[]), vec![]);
+    }
+
+    #[test]
+    fn test_merge_sort_immutable_non_copy() {
+        let collection = vec!["pear", "apple", "fig", "kiwi", "banana"]
+            .into_iter()
+            .map(String::from)
+            .collect::<Vec<String>>();
+
+        assert_eq!(
+            merge_sort_improved(&collection),
+            vec!["apple", "banana", "fig", "kiwi", "pear"]
+        );
+    }
+
+    test_sort!(
+        test_merge_sort_stable,
+        collection,
+        merge_sort_stable(&mut collection)
+    );
+
+    #[test]
+    fn test_merge_sort_stable_non_copy() {
+        let mut collection = vec!["pear", "apple", "fig", "kiwi", "banana", "cherry", "lime"]
+            .into_iter()
+            .map(String::from)
+            .collect::<Vec<String>>();
+
+        merge_sort_stable(&mut collection);
+
+        assert_eq!(
+            collection,
+            vec!["apple", "banana", "cherry", "fig", "kiwi", "lime", "pear"]
+        );
+
+        let mut empty_collection: Vec<String> = vec![];
+
+        merge_sort_stable(&mut empty_collection);
+
+        assert!(empty_collection.is_empty());
+    }
+
+    // Sorting by the secondary key, then by the primary one, works only if the sort is stable.
+    //
+    #[test]
+    fn test_merge_sort_stable_multi_key() {
+        #[derive(Debug, PartialEq)]
+        struct Person {
+            name: String,
+            age: u32,
+        }
+
+        let people = [
+            ("eve", 30),
+            ("bob", 25),
+            ("dan", 30),
+            ("amy", 25),
+            ("cal", 30),
+        ];
+
+        let mut collection = people
+            .iter()
+            .map(|(name, age)| Person {
+                name: name.to_string(),
+                age: *age,
+            })
+            .collect::<Vec<_>>();
+
+        merge_sort_stable_by(&mut collection, |a, b| a.name.cmp(&b.name));
+        merge_sort_stable_by(&mut collection, |a, b| a.age.cmp(&b.age));
+
+        let sorted_names = collection
+            .iter()
+            .map(|person| &person.name[..])
+            .collect::<Vec<_>>();
+
+        assert_eq!(sorted_names, vec!["amy", "bob", "cal", "dan", "eve"]);
+    }
+
+    #[test]
+    fn test_merge_sort_stable_large() {
+        let mut collection = (0..100_000)
+            .map(|i| (rand::random::<u8>(), i))
+            .collect::<Vec<(u8, i32)>>();
+
+        let mut expected_collection = collection.clone();
+        expected_collection.sort_by_key(|(key, _)| *key);
+
+        merge_sort_stable_by(&mut collection, |a, b| a.0.cmp(&b.0));
+
+        assert_eq!(collection, expected_collection);
+    }
+
+    test_sort_properties!(
+        test_merge_sort_improved_properties,
+        collection,
+        collection = merge_sort_improved(&collection),
+        stable = true
+    );
+
+    test_sort_properties!(
+        test_merge_sort_stable_properties,
+        collection,
+        merge_sort_stable(&mut collection),
+        stable = true
+    );
 }
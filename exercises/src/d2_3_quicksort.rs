@@ -1,4 +1,97 @@
+use crate::{
+    d2_5_sorter::partial_compare, d2_6_instrumented_sorts::SortObserver,
+    d2_7_introsort::introsort_by,
+};
 use rand::Rng;
+use rand_core::{impls, RngCore, SeedableRng};
+use std::cmp::Ordering;
+
+// Chooses the index of the pivot. Any random generator is a strategy (random pivot); the
+// deterministic strategies allow reproducing the same partitions on each run.
+//
+pub trait PivotStrategy {
+    fn choose<T, F: Fn(&T, &T) -> Ordering>(&mut self, collection: &[T], compare: &F) -> usize;
+}
+
+impl<R: RngCore> PivotStrategy for R {
+    fn choose<T, F: Fn(&T, &T) -> Ordering>(&mut self, collection: &[T], _compare: &F) -> usize {
+        self.gen_range(0..collection.len())
+    }
+}
+
+// The simplest choice; it's O(n^2) on sorted (and reversed) collections.
+//
+pub struct FirstPivot;
+
+impl PivotStrategy for FirstPivot {
+    fn choose<T, F: Fn(&T, &T) -> Ordering>(&mut self, _collection: &[T], _compare: &F) -> usize {
+        0
+    }
+}
+
+// Median of the first, middle and last elements.
+//
+pub struct MedianOfThreePivot;
+
+impl PivotStrategy for MedianOfThreePivot {
+    fn choose<T, F: Fn(&T, &T) -> Ordering>(&mut self, collection: &[T], compare: &F) -> usize {
+        let last = collection.len() - 1;
+
+        median_of_three(collection, 0, last / 2, last, compare)
+    }
+}
+
+// Tukey's ninther: median of the medians of three groups of three elements, spread across the
+// collection; it approximates the median better than the median of three, on large collections.
+//
+pub struct NintherPivot;
+
+// Below this size, the median of three is used.
+//
+const NINTHER_MIN_LEN: usize = 9;
+
+impl PivotStrategy for NintherPivot {
+    fn choose<T, F: Fn(&T, &T) -> Ordering>(&mut self, collection: &[T], compare: &F) -> usize {
+        if collection.len() < NINTHER_MIN_LEN {
+            return MedianOfThreePivot.choose(collection, compare);
+        }
+
+        let step = collection.len() / 9;
+        let median = |start: usize| {
+            median_of_three(collection, start, start + step, start + 2 * step, compare)
+        };
+
+        let (median_1, median_2, median_3) = (median(0), median(3 * step), median(6 * step));
+
+        median_of_three(collection, median_1, median_2, median_3, compare)
+    }
+}
+
+fn median_of_three<T, F: Fn(&T, &T) -> Ordering>(
+    collection: &[T],
+    a: usize,
+    b: usize,
+    c: usize,
+    compare: &F,
+) -> usize {
+    let less = |i: usize, j: usize| compare(&collection[i], &collection[j]) == Ordering::Less;
+
+    if less(a, b) {
+        if less(b, c) {
+            b
+        } else if less(a, c) {
+            c
+        } else {
+            a
+        }
+    } else if less(a, c) {
+        a
+    } else if less(b, c) {
+        c
+    } else {
+        b
+    }
+}
 
 // Iterating the collection using a chosen pivot, and move the lower elements to its left, and the
 // higher to its right. Since we can't move blocks of elements, when we find an element that it's
@@ -7,73 +100,264 @@ use rand::Rng;
 // higher than p values are always to its right.
 
 pub fn pivot<T: PartialOrd>(collection: &mut [T]) -> usize {
-    // Optimization: use a random element as pivot
-    //
-    let r = rand::thread_rng().gen_range(0..collection.len());
+    pivot_by(collection, partial_compare)
+}
+
+// Optimization: use a random element as pivot.
+//
+pub fn pivot_by<T, F: Fn(&T, &T) -> Ordering>(collection: &mut [T], compare: F) -> usize {
+    pivot_with(collection, compare, &mut rand::thread_rng())
+}
+
+pub fn pivot_with<T, F: Fn(&T, &T) -> Ordering, S: PivotStrategy>(
+    collection: &mut [T],
+    compare: F,
+    strategy: &mut S,
+) -> usize {
+    pivot_observed(collection, compare, strategy, 0, &mut ())
+}
+
+// The operations are reported to the observer (see `d2_6`); `offset` is the position of the
+// collection in the one being sorted.
+//
+pub fn pivot_observed<T, F: Fn(&T, &T) -> Ordering, S: PivotStrategy, O: SortObserver<T>>(
+    collection: &mut [T],
+    compare: F,
+    strategy: &mut S,
+    offset: usize,
+    observer: &mut O,
+) -> usize {
+    let r = strategy.choose(collection, &compare);
+    observer.swap(offset + r, offset);
     collection.swap(r, 0);
 
     let mut p = 0;
 
     for i in 1..collection.len() {
+        observer.compare(offset + i, offset + p);
+
         // Subtlety (for testing): values equal to the pivot will be to its right.
         //
-        if collection[i] < collection[p] {
+        if compare(&collection[i], &collection[p]) == Ordering::Less {
+            observer.swap(offset + p + 1, offset + i);
             collection.swap(p + 1, i);
+            observer.swap(offset + p, offset + p + 1);
             collection.swap(p, p + 1);
             p += 1;
         }
     }
 
+    observer.pivot(offset + p);
+
     p
 }
 
 pub fn quicksort<T: PartialOrd>(collection: &mut [T]) {
+    quicksort_by(collection, partial_compare)
+}
+
+pub fn quicksort_by<T, F: Fn(&T, &T) -> Ordering>(collection: &mut [T], compare: F) {
+    quicksort_with(collection, compare, &mut rand::thread_rng())
+}
+
+pub fn quicksort_with<T, F: Fn(&T, &T) -> Ordering, S: PivotStrategy>(
+    collection: &mut [T],
+    compare: F,
+    strategy: &mut S,
+) {
+    quicksort_rec(collection, 0, &compare, strategy, &mut ())
+}
+
+// The operations are reported to the observer (see `d2_6`).
+//
+pub fn quicksort_observed<T, F: Fn(&T, &T) -> Ordering, S: PivotStrategy, O: SortObserver<T>>(
+    collection: &mut [T],
+    compare: F,
+    strategy: &mut S,
+    observer: &mut O,
+) {
+    quicksort_rec(collection, 0, &compare, strategy, observer)
+}
+
+// See `merge_sort_improved_rec()` for the reason of passing the comparator by reference, and for
+// `offset`.
+//
+fn quicksort_rec<T, F: Fn(&T, &T) -> Ordering, S: PivotStrategy, O: SortObserver<T>>(
+    collection: &mut [T],
+    offset: usize,
+    compare: &F,
+    strategy: &mut S,
+    observer: &mut O,
+) {
+    observer.enter(offset, offset + collection.len());
+
     // The empty collection test can be performed here (include equality test), or for each subarray
     // after the split.
     //
     if collection.len() <= 1 {
+        observer.exit();
         return;
     }
 
-    let p = pivot(collection);
+    let p = pivot_observed(collection, compare, strategy, offset, observer);
 
     let (collection_1, collection_2) = collection.split_at_mut(p);
 
-    quicksort(collection_1);
-    quicksort(&mut collection_2[1..]);
+    quicksort_rec(collection_1, offset, compare, strategy, observer);
+    quicksort_rec(
+        &mut collection_2[1..],
+        offset + p + 1,
+        compare,
+        strategy,
+        observer,
+    );
+
+    observer.exit();
 }
 
-// Complexity require to make a parallel implementation, due to lifetimes.
-// To make this trivial, use Rayon or Crossbeam.
+// Three-way (Dutch national flag) partitioning: the elements equal to the pivot are grouped in the
+// middle, so that, with many duplicates, the partitions stay balanced, and the equal elements don't
+// need to be sorted anymore.
+//
+// Returns the equal range `(lt, gt)`: `[0, lt)` are lower than the pivot, `[lt, gt)` equal, and
+// `[gt, len)` higher.
 //
-pub struct RawSend<T>(*mut [T]);
-unsafe impl<T> Send for RawSend<T> {}
+pub fn pivot_3way<T: PartialOrd>(collection: &mut [T]) -> (usize, usize) {
+    pivot_3way_by(collection, partial_compare)
+}
+
+pub fn pivot_3way_by<T, F: Fn(&T, &T) -> Ordering>(
+    collection: &mut [T],
+    compare: F,
+) -> (usize, usize) {
+    pivot_3way_with(collection, compare, &mut rand::thread_rng())
+}
+
+pub fn pivot_3way_with<T, F: Fn(&T, &T) -> Ordering, S: PivotStrategy>(
+    collection: &mut [T],
+    compare: F,
+    strategy: &mut S,
+) -> (usize, usize) {
+    let r = strategy.choose(collection, &compare);
+    collection.swap(r, 0);
+
+    // `[lt, i)` are the equal elements found so far, so `lt` always points to an element equal to
+    // the pivot; `[i, gt)` are the elements not processed yet.
+    //
+    let (mut lt, mut i, mut gt) = (0, 1, collection.len());
+
+    while i < gt {
+        match compare(&collection[i], &collection[lt]) {
+            Ordering::Less => {
+                collection.swap(lt, i);
+                lt += 1;
+                i += 1;
+            }
+            Ordering::Greater => {
+                gt -= 1;
+                collection.swap(i, gt);
+            }
+            Ordering::Equal => {
+                i += 1;
+            }
+        }
+    }
+
+    (lt, gt)
+}
 
-pub fn threaded_quicksort<T: 'static + PartialOrd + Send>(collection: &mut [T]) {
+pub fn quicksort_3way<T: PartialOrd>(collection: &mut [T]) {
+    quicksort_3way_by(collection, partial_compare)
+}
+
+pub fn quicksort_3way_by<T, F: Fn(&T, &T) -> Ordering>(collection: &mut [T], compare: F) {
+    quicksort_3way_rec(collection, &compare)
+}
+
+fn quicksort_3way_rec<T, F: Fn(&T, &T) -> Ordering>(collection: &mut [T], compare: &F) {
     if collection.len() <= 1 {
         return;
     }
 
-    let p = pivot(collection);
+    let (lt, gt) = pivot_3way_by(collection, compare);
 
-    let (collection_1, collection_2) = collection.split_at_mut(p);
+    let (collection_1, collection_2) = collection.split_at_mut(lt);
+
+    quicksort_3way_rec(collection_1, compare);
+    quicksort_3way_rec(&mut collection_2[(gt - lt)..], compare);
+}
+
+// Scoped threads allow borrowing the subcollections (and the comparator), so that the partitions
+// can be sent to the spawned threads without `'static` bounds or unsafe pointer passing.
+//
+// Spawning a thread for each partition would create as many threads as elements, so the number of
+// threads is bounded: after a certain recursion depth, or below a certain partition size, the
+// partitions are sorted sequentially. The introsort is used for that, since the recursion depth of
+// the plain quicksort is unbounded (e.g. with many duplicates), and the spawned threads have small
+// stacks.
+//
+pub fn threaded_quicksort<T: PartialOrd + Send>(collection: &mut [T]) {
+    threaded_quicksort_by(collection, partial_compare)
+}
+
+pub fn threaded_quicksort_by<T: Send, F: Fn(&T, &T) -> Ordering + Sync>(
+    collection: &mut [T],
+    compare: F,
+) {
+    threaded_quicksort_rec(
+        collection,
+        &compare,
+        threaded_quicksort_max_depth(),
+        THREADED_QUICKSORT_MIN_LEN,
+    )
+}
+
+// Below this size, spawning a thread costs more than sorting the partition.
+//
+pub const THREADED_QUICKSORT_MIN_LEN: usize = 4096;
 
-    let collection_1 = collection_1 as *mut [T];
-    let collection_1 = RawSend(collection_1);
+// Each level doubles the number of threads, so log2(cores) levels are enough to use all the cores;
+// one extra level compensates for unbalanced partitions.
+//
+pub fn threaded_quicksort_max_depth() -> usize {
+    let cores = std::thread::available_parallelism().map_or(1, |cores| cores.get());
 
-    unsafe {
-        let thread = std::thread::spawn(move || {
-            threaded_quicksort(&mut *collection_1.0);
-        });
-        threaded_quicksort(&mut collection_2[1..]);
+    cores.next_power_of_two().trailing_zeros() as usize + 1
+}
+
+fn threaded_quicksort_rec<T: Send, F: Fn(&T, &T) -> Ordering + Sync>(
+    collection: &mut [T],
+    compare: &F,
+    max_depth: usize,
+    min_len: usize,
+) {
+    if collection.len() <= 1 {
+        return;
+    }
 
-        thread.join().ok();
+    if max_depth == 0 || collection.len() < min_len {
+        introsort_by(collection, compare);
+        return;
     }
+
+    let p = pivot_by(collection, compare);
+
+    let (collection_1, collection_2) = collection.split_at_mut(p);
+
+    std::thread::scope(|scope| {
+        scope.spawn(|| threaded_quicksort_rec(collection_1, compare, max_depth - 1, min_len));
+        threaded_quicksort_rec(&mut collection_2[1..], compare, max_depth - 1, min_len);
+    });
 }
 
-// Base random generator; on each iteration, the formula is `curr = (current * multiplier + increment) % modulo;`
-// Number should be prime-ish and large.
+// Base random generator (linear congruential); on each iteration, the formula is
+// `current = (current * multiplier + increment) % modulo`. The parameters determine the period (see
+// `has_full_period()`); bad ones yield short cycles.
+//
+// The intermediate values are computed as u128, so any usize parameters are valid (with a positive
+// modulo).
 //
+#[derive(Clone, Debug)]
 pub struct RandGen {
     pub current: usize,
     pub multiplier: usize,
@@ -81,10 +365,200 @@ pub struct RandGen {
     pub modulo: usize,
 }
 
+// Knuth's MMIX parameters; with a power of two modulo, they satisfy the full period conditions.
+// They don't fit a 32 bits `usize`, so there the (full period) glibc parameters are used.
+//
+#[cfg(target_pointer_width = "64")]
+const DEFAULT_MULTIPLIER: usize = 6_364_136_223_846_793_005;
+#[cfg(target_pointer_width = "64")]
+const DEFAULT_INCREMENT: usize = 1_442_695_040_888_963_407;
+#[cfg(target_pointer_width = "64")]
+const DEFAULT_MODULO: usize = 1 << 63;
+
+#[cfg(not(target_pointer_width = "64"))]
+const DEFAULT_MULTIPLIER: usize = 1_103_515_245;
+#[cfg(not(target_pointer_width = "64"))]
+const DEFAULT_INCREMENT: usize = 12_345;
+#[cfg(not(target_pointer_width = "64"))]
+const DEFAULT_MODULO: usize = 1 << 31;
+
+impl RandGen {
+    pub fn new(current: usize, multiplier: usize, increment: usize, modulo: usize) -> Self {
+        assert!(modulo > 0, "The modulo must be positive");
+
+        Self {
+            current: current % modulo,
+            multiplier,
+            increment,
+            modulo,
+        }
+    }
+
+    fn step(&self, current: usize) -> usize {
+        let (current, multiplier, increment, modulo) = (
+            current as u128,
+            self.multiplier as u128,
+            self.increment as u128,
+            self.modulo as u128,
+        );
+
+        ((current * multiplier + increment) % modulo) as usize
+    }
+
+    // Advances the generator by `steps` iterations, in O(log(steps)), so that parallel workers can
+    // use non-overlapping streams of the same sequence.
+    //
+    // `steps` iterations are the affine function `x -> A * x + C`; the functions for 1, 2, 4...
+    // steps are computed by composing the previous one with itself, and the ones corresponding to
+    // the bits of `steps` are composed into the result.
+    //
+    pub fn jump(&mut self, mut steps: u64) {
+        let modulo = self.modulo as u128;
+
+        let (mut total_multiplier, mut total_increment) = (1, 0);
+        let (mut multiplier, mut increment) = (self.multiplier as u128, self.increment as u128);
+
+        while steps > 0 {
+            if steps & 1 == 1 {
+                total_multiplier = total_multiplier * multiplier % modulo;
+                total_increment = (total_increment * multiplier + increment) % modulo;
+            }
+
+            increment = (multiplier + 1) * increment % modulo;
+            multiplier = multiplier * multiplier % modulo;
+
+            steps >>= 1;
+        }
+
+        self.current =
+            ((total_multiplier * self.current as u128 + total_increment) % modulo) as usize;
+    }
+
+    // Hull-Dobell theorem: the period is the modulo (for any seed) if and only if:
+    //
+    // - the increment and the modulo are coprime;
+    // - `multiplier - 1` is divisible by all the prime factors of the modulo;
+    // - `multiplier - 1` is divisible by 4, if the modulo is.
+    //
+    pub fn has_full_period(&self) -> bool {
+        let modulo = self.modulo as u128;
+        let multiplier_dec = (self.multiplier as u128 + modulo - 1) % modulo;
+
+        // The factors in common with `multiplier - 1` are removed from the modulo; if all its
+        // prime factors are shared, nothing is left.
+        //
+        let mut remaining = modulo;
+
+        loop {
+            let common = gcd(remaining, multiplier_dec);
+
+            if common == 1 {
+                break;
+            }
+
+            remaining /= common;
+        }
+
+        gcd(self.increment as u128, modulo) == 1
+            && remaining == 1
+            && (!modulo.is_multiple_of(4) || multiplier_dec.is_multiple_of(4))
+    }
+
+    // Length of the cycle the generator will enter, starting from the current value. If the period
+    // is not full, it's measured (Brent's algorithm), and `None` is returned if it's longer than
+    // `max_steps`.
+    //
+    pub fn period(&self, max_steps: usize) -> Option<usize> {
+        if self.has_full_period() {
+            return Some(self.modulo);
+        }
+
+        let (mut power, mut length) = (1, 1);
+        let mut tortoise = self.current;
+        let mut hare = self.step(tortoise);
+
+        for _ in 0..max_steps {
+            if tortoise == hare {
+                return Some(length);
+            }
+
+            if power == length {
+                tortoise = hare;
+                power *= 2;
+                length = 0;
+            }
+
+            hare = self.step(hare);
+            length += 1;
+        }
+
+        None
+    }
+}
+
+fn gcd(mut a: u128, mut b: u128) -> u128 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+
+    a
+}
+
+impl Iterator for RandGen {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.current = self.step(self.current);
+        Some(self.current)
+    }
+}
+
+// The values are scaled from `[0, modulo)` to 32 bits, which are the most significant bits of the
+// value, for a power of two modulo; for small moduli, only a few values are possible.
+//
+impl RngCore for RandGen {
+    fn next_u32(&mut self) -> u32 {
+        self.current = self.step(self.current);
+        (((self.current as u128) << 32) / self.modulo as u128) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        ((self.next_u32() as u64) << 32) | self.next_u32() as u64
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        impls::fill_bytes_via_next(self, dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+impl SeedableRng for RandGen {
+    type Seed = [u8; 8];
+
+    fn from_seed(seed: Self::Seed) -> Self {
+        Self::new(
+            u64::from_le_bytes(seed) as usize,
+            DEFAULT_MULTIPLIER,
+            DEFAULT_INCREMENT,
+            DEFAULT_MODULO,
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{quicksort, threaded_quicksort};
-    use crate::test_sort;
+    use super::{
+        pivot_with, quicksort, quicksort_3way, quicksort_3way_by, quicksort_by, quicksort_with,
+        threaded_quicksort, threaded_quicksort_by, FirstPivot, MedianOfThreePivot, NintherPivot,
+        PivotStrategy, RandGen,
+    };
+    use crate::{test_sort, test_sort_properties};
+    use rand::{rngs::StdRng, Rng, RngCore, SeedableRng};
+    use std::collections::HashMap;
 
     #[test]
     fn test_pivot() {
@@ -115,9 +589,371 @@ mod tests {
 
     test_sort!(test_quicksort, collection, quicksort(&mut collection));
 
+    test_sort!(
+        test_quicksort_by,
+        collection,
+        quicksort_by(&mut collection, |a, b| a.cmp(b))
+    );
+
+    test_sort!(
+        test_quicksort_with_first_pivot,
+        collection,
+        quicksort_with(&mut collection, |a, b| a.cmp(b), &mut FirstPivot)
+    );
+
+    test_sort!(
+        test_quicksort_with_median_of_three_pivot,
+        collection,
+        quicksort_with(&mut collection, |a, b| a.cmp(b), &mut MedianOfThreePivot)
+    );
+
+    test_sort!(
+        test_quicksort_with_ninther_pivot,
+        collection,
+        quicksort_with(&mut collection, |a, b| a.cmp(b), &mut NintherPivot)
+    );
+
+    test_sort!(
+        test_quicksort_with_seeded_rng,
+        collection,
+        quicksort_with(
+            &mut collection,
+            |a, b| a.cmp(b),
+            &mut StdRng::seed_from_u64(42)
+        )
+    );
+
+    #[test]
+    fn test_pivot_strategies() {
+        let collection = (0..100).collect::<Vec<_>>();
+        let compare = |a: &i32, b: &i32| a.cmp(b);
+
+        assert_eq!(FirstPivot.choose(&collection, &compare), 0);
+        assert_eq!(MedianOfThreePivot.choose(&collection, &compare), 49);
+        assert_eq!(MedianOfThreePivot.choose(&[3, 1, 2], &compare), 2);
+        assert_eq!(MedianOfThreePivot.choose(&[7], &compare), 0);
+
+        let reversed_collection = collection.iter().rev().copied().collect::<Vec<_>>();
+        let ninther = NintherPivot.choose(&reversed_collection, &compare);
+
+        assert!((40..60).contains(&reversed_collection[ninther]));
+
+        for len in 1..100 {
+            let collection = (0..len).collect::<Vec<_>>();
+
+            assert!(NintherPivot.choose(&collection, &compare) < collection.len());
+        }
+    }
+
+    // The same seed yields the same partitions.
+    //
+    #[test]
+    fn test_pivot_with_seeded_rng_reproducible() {
+        let collection = (0..1000).map(|_| rand::random::<u16>()).collect::<Vec<_>>();
+
+        let mut collection_1 = collection.clone();
+        let mut collection_2 = collection;
+
+        let p_1 = pivot_with(
+            &mut collection_1,
+            |a, b| a.cmp(b),
+            &mut StdRng::seed_from_u64(7),
+        );
+        let p_2 = pivot_with(
+            &mut collection_2,
+            |a, b| a.cmp(b),
+            &mut StdRng::seed_from_u64(7),
+        );
+
+        assert_eq!(p_1, p_2);
+        assert_eq!(collection_1, collection_2);
+    }
+
+    #[test]
+    fn test_pivot_3way() {
+        use super::*;
+
+        let mut collection = vec![
+            11, 1273, 11, 8273, 827, 11, 9172397, 2373, 2, 4, 11, 29831093, 287, 2837, 11, 92900,
+        ];
+
+        let (lt, gt) = pivot_3way(&mut collection);
+        let pivot_value = collection[lt];
+
+        assert!(lt < gt);
+
+        for (i, element) in collection.iter().enumerate() {
+            let expected_ordering = if i < lt {
+                Ordering::Less
+            } else if i < gt {
+                Ordering::Equal
+            } else {
+                Ordering::Greater
+            };
+
+            assert_eq!(
+                element.cmp(&pivot_value),
+                expected_ordering,
+                "Element {} in wrong position; array: {:?}",
+                element,
+                collection
+            );
+        }
+    }
+
+    test_sort!(
+        test_quicksort_3way,
+        collection,
+        quicksort_3way(&mut collection)
+    );
+
+    test_sort!(
+        test_quicksort_3way_by,
+        collection,
+        quicksort_3way_by(&mut collection, |a, b| a.cmp(b))
+    );
+
+    // With only a few distinct values, the recursion depth is bounded by their number, while the
+    // plain quicksort would recurse (at least) once per duplicate.
+    //
+    #[test]
+    fn test_quicksort_3way_duplicates() {
+        let mut collection = (0..1_000_000)
+            .map(|_| rand::random::<u8>() % 4)
+            .collect::<Vec<_>>();
+
+        let mut expected_collection = collection.clone();
+        expected_collection.sort();
+
+        quicksort_3way(&mut collection);
+
+        assert_eq!(collection, expected_collection);
+    }
+
     test_sort!(
         test_threaded_quicksort,
         collection,
         threaded_quicksort(&mut collection)
     );
+
+    test_sort!(
+        test_threaded_quicksort_by,
+        collection,
+        threaded_quicksort_by(&mut collection, |a, b| a.cmp(b))
+    );
+
+    #[test]
+    fn test_threaded_quicksort_large() {
+        let mut collection = (0..100_000)
+            .map(|_| rand::random::<u32>())
+            .collect::<Vec<_>>();
+
+        let mut expected_collection = collection.clone();
+        expected_collection.sort();
+
+        threaded_quicksort(&mut collection);
+
+        assert_eq!(collection, expected_collection);
+    }
+
+    // All-equal values make the plain quicksort recurse once per element (see `pivot()`), which would
+    // overflow the stacks of the spawned threads.
+    //
+    #[test]
+    fn test_threaded_quicksort_all_equal() {
+        let mut collection = vec![7; 1_000_000];
+
+        threaded_quicksort(&mut collection);
+
+        assert!(collection.iter().all(|value| *value == 7));
+    }
+
+    // Uses minimum limits, so that threads are spawned even for a small collection; the elements are
+    // borrowed, so they're not `'static`.
+    //
+    #[test]
+    fn test_threaded_quicksort_borrowed() {
+        use super::threaded_quicksort_rec;
+
+        let values = [
+            "pear", "apple", "fig", "kiwi", "banana", "cherry", "lime", "date",
+        ]
+        .iter()
+        .map(|value| value.to_string())
+        .collect::<Vec<String>>();
+
+        let mut collection = values.iter().collect::<Vec<&String>>();
+
+        threaded_quicksort_rec(&mut collection, &|a: &&String, b: &&String| a.cmp(b), 3, 1);
+
+        let mut expected_collection = values.iter().collect::<Vec<&String>>();
+        expected_collection.sort();
+
+        assert_eq!(collection, expected_collection);
+
+        // No size limit; all the partitions are split up to the (large) depth limit.
+        //
+        threaded_quicksort_rec(&mut collection, &|a: &&String, b: &&String| b.cmp(a), 64, 0);
+
+        expected_collection.reverse();
+
+        assert_eq!(collection, expected_collection);
+    }
+
+    test_sort_properties!(
+        test_quicksort_properties,
+        collection,
+        quicksort(&mut collection)
+    );
+
+    test_sort_properties!(
+        test_quicksort_3way_properties,
+        collection,
+        quicksort_3way(&mut collection)
+    );
+
+    test_sort_properties!(
+        test_threaded_quicksort_properties,
+        collection,
+        threaded_quicksort(&mut collection)
+    );
+
+    test_sort_properties!(
+        test_quicksort_with_ninther_pivot_properties,
+        collection,
+        quicksort_with(&mut collection, |a, b| a.cmp(b), &mut NintherPivot)
+    );
+
+    #[test]
+    fn test_rand_gen_iterator() {
+        let rand_gen = RandGen::new(23, 21321, 21323, 314);
+
+        let values = rand_gen.take(4).collect::<Vec<_>>();
+
+        assert_eq!(values, vec![200, 51, 274, 269]);
+    }
+
+    #[test]
+    fn test_rand_gen_jump() {
+        let rand_gens = vec![
+            RandGen::new(23, 21321, 21323, 314),
+            RandGen::new(1, 1_103_515_245, 12_345, 1 << 31),
+            RandGen::seed_from_u64(42),
+            RandGen::new(7, usize::MAX, usize::MAX, usize::MAX),
+        ];
+
+        for rand_gen in rand_gens {
+            let mut stepped_rand_gen = rand_gen.clone();
+
+            for steps in 0..200 {
+                let mut jumped_rand_gen = rand_gen.clone();
+                jumped_rand_gen.jump(steps);
+
+                assert_eq!(jumped_rand_gen.current, stepped_rand_gen.current);
+
+                stepped_rand_gen.next();
+            }
+        }
+    }
+
+    // Two workers, one of which jumps ahead, produce the two halves of the same sequence.
+    //
+    #[test]
+    fn test_rand_gen_streams() {
+        let rand_gen = RandGen::seed_from_u64(1);
+
+        let mut worker_1 = rand_gen.clone();
+        let mut worker_2 = rand_gen.clone();
+        worker_2.jump(1000);
+
+        let streams = (0..1000)
+            .map(|_| worker_1.next_u32())
+            .chain((0..1000).map(|_| worker_2.next_u32()))
+            .collect::<Vec<_>>();
+
+        let mut rand_gen = rand_gen;
+        let sequence = (0..2000).map(|_| rand_gen.next_u32()).collect::<Vec<_>>();
+
+        assert_eq!(streams, sequence);
+    }
+
+    #[test]
+    fn test_rand_gen_period() {
+        // Brute force: the cycle length is the distance from the first repeated value.
+        //
+        let measure_period = |mut rand_gen: RandGen| {
+            let mut positions = HashMap::new();
+
+            for position in 0.. {
+                if let Some(previous_position) = positions.insert(rand_gen.current, position) {
+                    return position - previous_position;
+                }
+
+                rand_gen.next();
+            }
+
+            unreachable!()
+        };
+
+        assert!(RandGen::seed_from_u64(0).has_full_period());
+        assert_eq!(
+            RandGen::seed_from_u64(0).period(0),
+            Some(super::DEFAULT_MODULO)
+        );
+
+        for multiplier in 0..32 {
+            for increment in 0..32 {
+                for modulo in [1, 2, 16, 30, 31, 36] {
+                    let rand_gen = RandGen::new(3, multiplier, increment, modulo);
+                    let measured_period = measure_period(rand_gen.clone());
+
+                    assert_eq!(
+                        rand_gen.has_full_period(),
+                        measured_period == modulo,
+                        "{:?}",
+                        rand_gen
+                    );
+                    assert_eq!(rand_gen.period(1000), Some(measured_period));
+                }
+            }
+        }
+
+        let rand_gen = RandGen::new(23, 21321, 21323, 314);
+
+        assert!(!rand_gen.has_full_period());
+        assert_eq!(rand_gen.period(1000), Some(measure_period(rand_gen)));
+
+        assert_eq!(RandGen::new(1, 3, 1, 1 << 62).period(1000), None);
+    }
+
+    #[test]
+    fn test_rand_gen_rng() {
+        let values_1 = RandGen::seed_from_u64(5)
+            .sample_iter(rand::distributions::Standard)
+            .take(16)
+            .collect::<Vec<u64>>();
+        let values_2 = RandGen::from_seed(RandGen::seed_from_u64(5).current.to_le_bytes())
+            .sample_iter(rand::distributions::Standard)
+            .take(16)
+            .collect::<Vec<u64>>();
+
+        assert_eq!(values_1, values_2);
+
+        let mut rand_gen = RandGen::seed_from_u64(5);
+        let mut bytes = [0; 13];
+        rand_gen.fill_bytes(&mut bytes);
+
+        assert!(bytes.iter().any(|byte| *byte != 0));
+        assert!((0..1000).all(|_| rand_gen.gen_range(10..20) < 20));
+    }
+
+    test_sort!(
+        test_quicksort_with_rand_gen,
+        collection,
+        quicksort_with(
+            &mut collection,
+            |a, b| a.cmp(b),
+            &mut RandGen::seed_from_u64(3)
+        )
+    );
 }
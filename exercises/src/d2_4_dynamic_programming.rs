@@ -1,3 +1,8 @@
+use crate::d5_4_finishing_hashmap::HMap;
+use num_bigint::BigUint;
+use num_traits::{One, Zero};
+use std::{collections::HashMap, fmt, hash::Hash, marker::PhantomData};
+
 // The name "dynamic" is somewhat misleading. Dynamic programming means essentially to store and reuse
 // the computed values.
 
@@ -19,14 +24,675 @@ pub fn fibonacci_dynamic(n: u32) -> (u32, u32) {
     (last + beforelast, last)
 }
 
+// The variants below follow the same convention as the ones above: `fibonacci(0) = fibonacci(1) = 1`.
+
+// Returns None if the result doesn't fit an u32 (from n = 47).
+//
+pub fn fibonacci_checked(n: u32) -> Option<u32> {
+    fibonacci_dynamic_checked(n).ok().map(|(last, _)| last)
+}
+
+#[derive(Debug, PartialEq)]
+pub struct FibonacciOverflow {
+    // First n whose result doesn't fit.
+    //
+    pub n: u32,
+}
+
+impl fmt::Display for FibonacciOverflow {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Fibonacci number {} overflows", self.n)
+    }
+}
+
+impl std::error::Error for FibonacciOverflow {}
+
+// Iterative version of `fibonacci_dynamic()`, which doesn't overflow the stack for large n.
+//
+pub fn fibonacci_dynamic_checked(n: u32) -> Result<(u32, u32), FibonacciOverflow> {
+    let (mut last, mut beforelast) = (1_u32, 0);
+
+    for i in 1..=n {
+        let next = last
+            .checked_add(beforelast)
+            .ok_or(FibonacciOverflow { n: i })?;
+
+        beforelast = last;
+        last = next;
+    }
+
+    Ok((last, beforelast))
+}
+
+// Arbitrary precision; O(n) additions.
+//
+pub fn fibonacci_big(n: u64) -> BigUint {
+    let (mut last, mut beforelast) = (BigUint::one(), BigUint::zero());
+
+    for _ in 0..n {
+        let next = &last + &beforelast;
+        beforelast = std::mem::replace(&mut last, next);
+    }
+
+    last
+}
+
+// O(log(n)) multiplications, based on the identities (with the standard numbering, F(0) = 0):
+//
+//   F(2k)     = F(k) * (2 * F(k + 1) - F(k))
+//   F(2k + 1) = F(k)^2 + F(k + 1)^2
+//
+// The bits of n are processed from the most significant; each one doubles k, and adds 1 if set.
+//
+pub fn fibonacci_fast_doubling(n: u64) -> BigUint {
+    // The standard F(n + 1) is the result, so one more bit may be needed.
+    //
+    let n = n as u128 + 1;
+
+    // (F(k), F(k + 1))
+    //
+    let (mut f_k, mut f_k_1) = (BigUint::zero(), BigUint::one());
+
+    for bit in (0..(128 - n.leading_zeros())).rev() {
+        let f_2k = &f_k * (&f_k_1 * 2_u32 - &f_k);
+        let f_2k_1 = &f_k * &f_k + &f_k_1 * &f_k_1;
+
+        if n & (1 << bit) == 0 {
+            f_k = f_2k;
+            f_k_1 = f_2k_1;
+        } else {
+            f_k_1 = &f_2k + &f_2k_1;
+            f_k = f_2k_1;
+        }
+    }
+
+    f_k
+}
+
+// O(log(n)) matrix multiplications: [[1, 1], [1, 0]]^n = [[F(n + 1), F(n)], [F(n), F(n - 1)]]. The
+// power is computed by squaring.
+//
+pub fn fibonacci_matrix(mut n: u64) -> BigUint {
+    type Matrix = [[BigUint; 2]; 2];
+
+    let multiply = |a: &Matrix, b: &Matrix| -> Matrix {
+        [
+            [
+                &a[0][0] * &b[0][0] + &a[0][1] * &b[1][0],
+                &a[0][0] * &b[0][1] + &a[0][1] * &b[1][1],
+            ],
+            [
+                &a[1][0] * &b[0][0] + &a[1][1] * &b[1][0],
+                &a[1][0] * &b[0][1] + &a[1][1] * &b[1][1],
+            ],
+        ]
+    };
+
+    let mut result: Matrix = [
+        [BigUint::one(), BigUint::zero()],
+        [BigUint::zero(), BigUint::one()],
+    ];
+    let mut power: Matrix = [
+        [BigUint::one(), BigUint::one()],
+        [BigUint::one(), BigUint::zero()],
+    ];
+
+    while n > 0 {
+        if n & 1 == 1 {
+            result = multiply(&result, &power);
+        }
+
+        power = multiply(&power, &power);
+        n >>= 1;
+    }
+
+    // F(n + 1)
+    //
+    result[0][0].clone()
+}
+
+// Storage for the memoized values; implemented for the std HashMap, and our HMap.
+//
+pub trait MemoStore<K, V> {
+    fn get(&self, key: &K) -> Option<&V>;
+    fn insert(&mut self, key: K, value: V);
+}
+
+impl<K: Hash + Eq, V> MemoStore<K, V> for HashMap<K, V> {
+    fn get(&self, key: &K) -> Option<&V> {
+        HashMap::get(self, key)
+    }
+
+    fn insert(&mut self, key: K, value: V) {
+        HashMap::insert(self, key, value);
+    }
+}
+
+impl<K: Hash + Eq, V> MemoStore<K, V> for HMap<K, V> {
+    fn get(&self, key: &K) -> Option<&V> {
+        HMap::get(self, key)
+    }
+
+    fn insert(&mut self, key: K, value: V) {
+        HMap::insert(self, key, value);
+    }
+}
+
+// Memoizes a recursive function. The function receives, instead of calling itself, a function that
+// returns the (memoized) values of the subproblems, e.g.:
+//
+//   let fibonacci = |fibonacci: &mut dyn FnMut(u64) -> u64, n: u64| {
+//       if n <= 1 { 1 } else { fibonacci(n - 1) + fibonacci(n - 2) }
+//   };
+//
+//   Memo::new().get(50, &fibonacci);
+//
+// The recursion is not removed, so the depth is bounded by the stack size.
+//
+pub struct Memo<K, V, S = HashMap<K, V>> {
+    store: S,
+    entries: PhantomData<(K, V)>,
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> Memo<K, V> {
+    pub fn new() -> Self {
+        Self::with_store(HashMap::new())
+    }
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> Default for Memo<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Hash + Eq + Clone, V: Clone, S: MemoStore<K, V>> Memo<K, V, S> {
+    pub fn with_store(store: S) -> Self {
+        Self {
+            store,
+            entries: PhantomData,
+        }
+    }
+
+    pub fn get<F: Fn(&mut dyn FnMut(K) -> V, K) -> V>(&mut self, key: K, function: &F) -> V {
+        if let Some(value) = self.store.get(&key) {
+            return value.clone();
+        }
+
+        let value = function(&mut |sub_key| self.get(sub_key, function), key.clone());
+
+        self.store.insert(key, value.clone());
+
+        value
+    }
+}
+
+// The problems below are solved bottom-up: a table of the solutions of all the subproblems is
+// filled, then the choices leading to the optimal value are retraced from the end of the table.
+
+// Returns the length, and one of the longest subsequences common to both collections.
+//
+// `lengths[i][j]` is the LCS length of `a[..i]` and `b[..j]`.
+//
+pub fn longest_common_subsequence<T: PartialEq + Clone>(a: &[T], b: &[T]) -> (usize, Vec<T>) {
+    let mut lengths = vec![vec![0; b.len() + 1]; a.len() + 1];
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            lengths[i][j] = if a[i - 1] == b[j - 1] {
+                lengths[i - 1][j - 1] + 1
+            } else {
+                lengths[i - 1][j].max(lengths[i][j - 1])
+            };
+        }
+    }
+
+    let mut subsequence = vec![];
+    let (mut i, mut j) = (a.len(), b.len());
+
+    while i > 0 && j > 0 {
+        if a[i - 1] == b[j - 1] {
+            subsequence.push(a[i - 1].clone());
+            i -= 1;
+            j -= 1;
+        } else if lengths[i - 1][j] >= lengths[i][j - 1] {
+            i -= 1;
+        } else {
+            j -= 1;
+        }
+    }
+
+    subsequence.reverse();
+
+    (lengths[a.len()][b.len()], subsequence)
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum EditOperation<T> {
+    Keep(T),
+    Substitute(T, T),
+    Insert(T),
+    Delete(T),
+}
+
+// Levenshtein distance; returns the distance, and the operations that, applied in order, transform
+// `a` into `b` (each operation, except `Keep`, costs 1).
+//
+// `distances[i][j]` is the distance between `a[..i]` and `b[..j]`.
+//
+pub fn edit_distance<T: PartialEq + Clone>(a: &[T], b: &[T]) -> (usize, Vec<EditOperation<T>>) {
+    let mut distances = vec![vec![0; b.len() + 1]; a.len() + 1];
+
+    for (i, row) in distances.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, distance) in distances[0].iter_mut().enumerate() {
+        *distance = j;
+    }
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let substitution_cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+
+            distances[i][j] = (distances[i - 1][j - 1] + substitution_cost)
+                .min(distances[i - 1][j] + 1)
+                .min(distances[i][j - 1] + 1);
+        }
+    }
+
+    let mut operations = vec![];
+    let (mut i, mut j) = (a.len(), b.len());
+
+    while i > 0 || j > 0 {
+        if i > 0 && j > 0 && a[i - 1] == b[j - 1] && distances[i][j] == distances[i - 1][j - 1] {
+            operations.push(EditOperation::Keep(a[i - 1].clone()));
+            i -= 1;
+            j -= 1;
+        } else if i > 0 && j > 0 && distances[i][j] == distances[i - 1][j - 1] + 1 {
+            operations.push(EditOperation::Substitute(
+                a[i - 1].clone(),
+                b[j - 1].clone(),
+            ));
+            i -= 1;
+            j -= 1;
+        } else if i > 0 && distances[i][j] == distances[i - 1][j] + 1 {
+            operations.push(EditOperation::Delete(a[i - 1].clone()));
+            i -= 1;
+        } else {
+            operations.push(EditOperation::Insert(b[j - 1].clone()));
+            j -= 1;
+        }
+    }
+
+    operations.reverse();
+
+    (distances[a.len()][b.len()], operations)
+}
+
+// 0/1 knapsack; the items are (weight, value) pairs. Returns the highest total value, and the
+// indexes of the chosen items.
+//
+// `values[i][w]` is the highest value using the first `i` items, with capacity `w`.
+//
+pub fn knapsack(items: &[(usize, usize)], capacity: usize) -> (usize, Vec<usize>) {
+    let mut values = vec![vec![0; capacity + 1]; items.len() + 1];
+
+    for (i, (weight, value)) in items.iter().enumerate() {
+        for w in 0..=capacity {
+            values[i + 1][w] = values[i][w];
+
+            if *weight <= w {
+                values[i + 1][w] = values[i + 1][w].max(values[i][w - weight] + value);
+            }
+        }
+    }
+
+    let mut chosen_items = vec![];
+    let mut w = capacity;
+
+    for i in (0..items.len()).rev() {
+        if values[i + 1][w] != values[i][w] {
+            chosen_items.push(i);
+            w -= items[i].0;
+        }
+    }
+
+    chosen_items.reverse();
+
+    (values[items.len()][capacity], chosen_items)
+}
+
+// Fewest coins (of unlimited supply) adding up to the amount; returns their number, and the coins,
+// or None if the amount can't be made.
+//
+// `counts[a]` is the fewest coins for the amount `a`, and `last_coins[a]` the last coin added.
+//
+pub fn coin_change(coins: &[usize], amount: usize) -> Option<(usize, Vec<usize>)> {
+    let mut counts = vec![None; amount + 1];
+    let mut last_coins = vec![0; amount + 1];
+
+    counts[0] = Some(0);
+
+    for a in 1..=amount {
+        for coin in coins {
+            if *coin == 0 || *coin > a {
+                continue;
+            }
+
+            if let Some(count) = counts[a - coin] {
+                if counts[a].is_none_or(|current_count| count + 1 < current_count) {
+                    counts[a] = Some(count + 1);
+                    last_coins[a] = *coin;
+                }
+            }
+        }
+    }
+
+    let count = counts[amount]?;
+
+    let mut used_coins = vec![];
+    let mut a = amount;
+
+    while a > 0 {
+        used_coins.push(last_coins[a]);
+        a -= last_coins[a];
+    }
+
+    Some((count, used_coins))
+}
+
+// Longest strictly increasing subsequence, in O(n * log(n)) (patience sorting); returns the length,
+// and one of the longest subsequences.
+//
+// `tails[k]` is the index of the lowest element ending an increasing subsequence of length `k + 1`;
+// the tails are increasing, so the position of each element can be binary searched. Each element
+// links to the previous one in its subsequence, so that the longest one can be retraced.
+//
+pub fn longest_increasing_subsequence<T: PartialOrd + Clone>(collection: &[T]) -> (usize, Vec<T>) {
+    let mut tails: Vec<usize> = vec![];
+    let mut previous = vec![None; collection.len()];
+
+    for (i, value) in collection.iter().enumerate() {
+        let k = tails.partition_point(|tail| collection[*tail] < *value);
+
+        if k > 0 {
+            previous[i] = Some(tails[k - 1]);
+        }
+
+        if k == tails.len() {
+            tails.push(i);
+        } else {
+            tails[k] = i;
+        }
+    }
+
+    let mut subsequence = vec![];
+    let mut current = tails.last().copied();
+
+    while let Some(i) = current {
+        subsequence.push(collection[i].clone());
+        current = previous[i];
+    }
+
+    subsequence.reverse();
+
+    (tails.len(), subsequence)
+}
+
 #[cfg(test)]
 mod tests {
+    use super::*;
+
     #[test]
     fn test_fibonacci_dynamic() {
-        use super::*;
-
         for i in 0..20 {
             assert_eq!(fibonacci(i), fibonacci_dynamic(i).0);
         }
     }
+
+    #[test]
+    fn test_fibonacci_variants_agreement() {
+        for i in 0..=46 {
+            let expected = fibonacci_dynamic_checked(i).unwrap();
+
+            if i <= 25 {
+                assert_eq!(fibonacci(i), expected.0);
+                assert_eq!(fibonacci_dynamic(i), expected);
+            }
+
+            assert_eq!(fibonacci_checked(i), Some(expected.0));
+            assert_eq!(fibonacci_big(i as u64), BigUint::from(expected.0));
+            assert_eq!(fibonacci_fast_doubling(i as u64), BigUint::from(expected.0));
+            assert_eq!(fibonacci_matrix(i as u64), BigUint::from(expected.0));
+        }
+
+        for i in (47..2000).step_by(7) {
+            let expected = fibonacci_big(i);
+
+            assert_eq!(fibonacci_fast_doubling(i), expected);
+            assert_eq!(fibonacci_matrix(i), expected);
+        }
+    }
+
+    #[test]
+    fn test_fibonacci_overflow() {
+        assert_eq!(fibonacci_checked(46), Some(2_971_215_073));
+        assert_eq!(fibonacci_checked(47), None);
+
+        let error = fibonacci_dynamic_checked(1000).unwrap_err();
+
+        assert_eq!(error, FibonacciOverflow { n: 47 });
+        assert_eq!(error.to_string(), "Fibonacci number 47 overflows");
+    }
+
+    #[test]
+    fn test_fibonacci_huge() {
+        // F(100), in the standard numbering.
+        //
+        let expected = "354224848179261915075".parse::<BigUint>().unwrap();
+
+        assert_eq!(fibonacci_big(99), expected);
+        assert_eq!(fibonacci_fast_doubling(99), expected);
+
+        // F(n) has about n * log10(phi) digits.
+        //
+        let result = fibonacci_fast_doubling(100_000);
+
+        assert_eq!(result.to_string().len(), 20_899);
+        assert_eq!(result, fibonacci_matrix(100_000));
+    }
+
+    #[test]
+    fn test_memo() {
+        let fibonacci_memoized = |fibonacci: &mut dyn FnMut(u32) -> u32, n: u32| {
+            if n <= 1 {
+                1
+            } else {
+                fibonacci(n - 1) + fibonacci(n - 2)
+            }
+        };
+
+        let mut memo = Memo::new();
+
+        for i in 0..20 {
+            assert_eq!(memo.get(i, &fibonacci_memoized), fibonacci(i));
+        }
+
+        let mut memo = Memo::with_store(HMap::new());
+
+        assert_eq!(memo.get(40, &fibonacci_memoized), fibonacci_dynamic(40).0);
+    }
+
+    // Paths in a grid, moving only right or down; the key is a tuple.
+    //
+    #[test]
+    fn test_memo_grid_paths() {
+        let paths = |paths: &mut dyn FnMut((u64, u64)) -> u64, (x, y): (u64, u64)| {
+            if x == 0 || y == 0 {
+                1
+            } else {
+                paths((x - 1, y)) + paths((x, y - 1))
+            }
+        };
+
+        // Binomial coefficient (32 choose 16).
+        //
+        assert_eq!(Memo::new().get((16, 16), &paths), 601_080_390);
+        assert_eq!(
+            Memo::with_store(HMap::new()).get((16, 16), &paths),
+            601_080_390
+        );
+    }
+
+    #[test]
+    fn test_longest_common_subsequence() {
+        let (length, subsequence) =
+            longest_common_subsequence(b"AGGTAB".as_ref(), b"GXTXAYB".as_ref());
+
+        assert_eq!(length, 4);
+        assert_eq!(subsequence, b"GTAB");
+
+        assert_eq!(longest_common_subsequence::<u8>(&[], b"ABC"), (0, vec![]));
+
+        let (length, subsequence) = longest_common_subsequence(&[1, 2, 3, 4], &[5, 6]);
+
+        assert_eq!((length, subsequence), (0, vec![]));
+    }
+
+    // Applies the operations to `a`, checking that they're consistent with it.
+    //
+    fn apply_edit_operations<T: PartialEq + Clone + std::fmt::Debug>(
+        a: &[T],
+        operations: &[EditOperation<T>],
+    ) -> Vec<T> {
+        let mut a = a.iter();
+        let mut result = vec![];
+
+        for operation in operations {
+            match operation {
+                EditOperation::Keep(value) => {
+                    assert_eq!(a.next(), Some(value));
+                    result.push(value.clone());
+                }
+                EditOperation::Substitute(old_value, new_value) => {
+                    assert_eq!(a.next(), Some(old_value));
+                    assert_ne!(old_value, new_value);
+                    result.push(new_value.clone());
+                }
+                EditOperation::Insert(value) => result.push(value.clone()),
+                EditOperation::Delete(value) => assert_eq!(a.next(), Some(value)),
+            }
+        }
+
+        assert_eq!(a.next(), None);
+
+        result
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        let pairs = [
+            ("kitten", "sitting", 3),
+            ("sunday", "saturday", 3),
+            ("", "abc", 3),
+            ("abc", "", 3),
+            ("same", "same", 0),
+            ("flaw", "lawn", 2),
+        ];
+
+        for (a, b, expected_distance) in pairs.iter() {
+            let (a, b) = (a.as_bytes(), b.as_bytes());
+            let (distance, operations) = edit_distance(a, b);
+
+            assert_eq!(distance, *expected_distance);
+            assert_eq!(apply_edit_operations(a, &operations), b);
+
+            let costly_operations = operations
+                .iter()
+                .filter(|operation| !matches!(operation, EditOperation::Keep(_)))
+                .count();
+
+            assert_eq!(costly_operations, distance);
+        }
+    }
+
+    #[test]
+    fn test_knapsack() {
+        let items = [(10, 60), (20, 100), (30, 120)];
+
+        assert_eq!(knapsack(&items, 50), (220, vec![1, 2]));
+        assert_eq!(knapsack(&items, 0), (0, vec![]));
+        assert_eq!(knapsack(&[], 10), (0, vec![]));
+
+        let items = [(5, 10), (4, 40), (6, 30), (3, 50)];
+
+        let (value, chosen_items) = knapsack(&items, 10);
+
+        assert_eq!(value, 90);
+        assert_eq!(chosen_items, vec![1, 3]);
+    }
+
+    #[test]
+    fn test_coin_change() {
+        let (count, mut used_coins) = coin_change(&[1, 5, 10, 25], 63).unwrap();
+        used_coins.sort();
+
+        assert_eq!(count, 6);
+        assert_eq!(used_coins, vec![1, 1, 1, 10, 25, 25]);
+
+        // The greedy choice (4 + 1 + 1) is not optimal.
+        //
+        let (count, used_coins) = coin_change(&[1, 3, 4], 6).unwrap();
+
+        assert_eq!((count, used_coins), (2, vec![3, 3]));
+
+        assert_eq!(coin_change(&[2], 3), None);
+        assert_eq!(coin_change(&[2], 0), Some((0, vec![])));
+    }
+
+    #[test]
+    fn test_longest_increasing_subsequence() {
+        let (length, subsequence) = longest_increasing_subsequence(&[10, 9, 2, 5, 3, 7, 101, 18]);
+
+        assert_eq!(length, 4);
+        assert_eq!(subsequence, vec![2, 3, 7, 18]);
+
+        assert_eq!(longest_increasing_subsequence(&[5, 5, 5]), (1, vec![5]));
+        assert_eq!(longest_increasing_subsequence::<i32>(&[]), (0, vec![]));
+    }
+
+    // Checks against a brute force solution (all subsets), on small random collections.
+    //
+    #[test]
+    fn test_longest_increasing_subsequence_brute_force() {
+        for _ in 0..100 {
+            let collection = (0..12)
+                .map(|_| rand::random::<u8>() % 10)
+                .collect::<Vec<_>>();
+
+            let expected_length = (0_u32..1 << collection.len())
+                .map(|mask| {
+                    collection
+                        .iter()
+                        .enumerate()
+                        .filter(|(i, _)| mask & (1 << i) != 0)
+                        .map(|(_, value)| *value)
+                        .collect::<Vec<_>>()
+                })
+                .filter(|subset| subset.windows(2).all(|pair| pair[0] < pair[1]))
+                .map(|subset| subset.len())
+                .max()
+                .unwrap();
+
+            let (length, subsequence) = longest_increasing_subsequence(&collection);
+
+            assert_eq!(length, expected_length);
+            assert_eq!(subsequence.len(), length);
+            assert!(subsequence.windows(2).all(|pair| pair[0] < pair[1]));
+            assert_eq!(
+                longest_common_subsequence(&collection, &subsequence).0,
+                length
+            );
+        }
+    }
 }
@@ -0,0 +1,350 @@
+use crate::{
+    d2_1_bubble_sort::bubble_sort_by,
+    d2_2_merge_sort::{merge_sort_improved_by, merge_sort_stable_by},
+    d2_3_quicksort::{quicksort_3way_by, quicksort_by, threaded_quicksort_by},
+};
+use serde_derive::{Deserialize, Serialize};
+use std::cmp::Ordering;
+
+// Common interface to the sorting algorithms, so that they can be swapped, and used with a custom
+// ordering (e.g. reverse, or by a field) without wrapping the elements in newtypes.
+//
+// Implementors need to provide only `sort_by()`. The comparator is required to be `Sync`, so that
+// it can be shared by the threaded algorithms.
+//
+pub trait Sorter<T> {
+    fn sort_by<F: Fn(&T, &T) -> Ordering + Sync>(&self, collection: &mut [T], compare: F);
+
+    fn sort(&self, collection: &mut [T])
+    where
+        T: PartialOrd,
+    {
+        self.sort_by(collection, partial_compare)
+    }
+
+    fn sort_by_key<K: PartialOrd, F: Fn(&T) -> K + Sync>(&self, collection: &mut [T], key: F) {
+        self.sort_by(collection, |a, b| partial_compare(&key(a), &key(b)))
+    }
+
+    fn sort_floats(&self, collection: &mut [T], order: FloatOrder)
+    where
+        T: Float,
+    {
+        self.sort_by(collection, |a, b| order.compare(a, b))
+    }
+}
+
+// Comparator equivalent to the `<`/`>` operators; incomparable values (e.g. NaN) are considered
+// equal, which is what the operator-based algorithms implicitly do.
+//
+pub fn partial_compare<T: PartialOrd + ?Sized>(a: &T, b: &T) -> Ordering {
+    a.partial_cmp(b).unwrap_or(Ordering::Equal)
+}
+
+// With `partial_compare()`, a NaN is "equal" to every value, so the comparator is inconsistent (e.g.
+// 1 = NaN = 0, but 1 > 0), and the algorithms produce an arbitrary order. The floats are instead
+// compared with a total order:
+//
+// - `Total`: IEEE 754 totalOrder, that is, -NaN < -inf < ... < -0 < +0 < ... < +inf < +NaN;
+// - `NanFirst`/`NanLast`: the NaNs (regardless of their sign) are placed at the start/end, and are
+//   equal to each other; the other values are in total order, so -0 comes before +0.
+//
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FloatOrder {
+    Total,
+    NanFirst,
+    NanLast,
+}
+
+impl FloatOrder {
+    pub fn compare<T: Float>(self, a: &T, b: &T) -> Ordering {
+        match (self, a.is_nan(), b.is_nan()) {
+            (FloatOrder::Total, _, _) | (_, false, false) => a.total_compare(b),
+            (_, true, true) => Ordering::Equal,
+            (FloatOrder::NanFirst, true, false) | (FloatOrder::NanLast, false, true) => {
+                Ordering::Less
+            }
+            (FloatOrder::NanFirst, false, true) | (FloatOrder::NanLast, true, false) => {
+                Ordering::Greater
+            }
+        }
+    }
+}
+
+pub trait Float: Copy {
+    fn total_compare(&self, other: &Self) -> Ordering;
+
+    fn is_nan(&self) -> bool;
+}
+
+macro_rules! impl_float {
+    ($($type:ty),*) => {
+        $(
+            impl Float for $type {
+                fn total_compare(&self, other: &Self) -> Ordering {
+                    self.total_cmp(other)
+                }
+
+                fn is_nan(&self) -> bool {
+                    <$type>::is_nan(*self)
+                }
+            }
+        )*
+    };
+}
+
+impl_float!(f32, f64);
+
+// Comparator for the IEEE 754 total order (see `FloatOrder`).
+//
+pub fn total_compare<T: Float>(a: &T, b: &T) -> Ordering {
+    a.total_compare(b)
+}
+
+// Float wrapper ordered by the IEEE 754 total order, for the algorithms that don't take a comparator,
+// like the external sort, which requires `Ord`.
+//
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+pub struct TotalOrder<T>(pub T);
+
+impl<T: Float> PartialEq for TotalOrder<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<T: Float> Eq for TotalOrder<T> {}
+
+impl<T: Float> PartialOrd for TotalOrder<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: Float> Ord for TotalOrder<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_compare(&other.0)
+    }
+}
+
+pub struct BubbleSort;
+pub struct MergeSort;
+pub struct StableMergeSort;
+pub struct QuickSort;
+pub struct QuickSort3Way;
+pub struct ThreadedQuickSort;
+
+impl<T> Sorter<T> for BubbleSort {
+    fn sort_by<F: Fn(&T, &T) -> Ordering + Sync>(&self, collection: &mut [T], compare: F) {
+        bubble_sort_by(collection, compare)
+    }
+}
+
+// The merge sort is not in place, so the sorted values are cloned back.
+//
+impl<T: Clone> Sorter<T> for MergeSort {
+    fn sort_by<F: Fn(&T, &T) -> Ordering + Sync>(&self, collection: &mut [T], compare: F) {
+        let sorted_collection = merge_sort_improved_by(collection, compare);
+        collection.clone_from_slice(&sorted_collection);
+    }
+}
+
+impl<T> Sorter<T> for StableMergeSort {
+    fn sort_by<F: Fn(&T, &T) -> Ordering + Sync>(&self, collection: &mut [T], compare: F) {
+        merge_sort_stable_by(collection, compare)
+    }
+}
+
+impl<T> Sorter<T> for QuickSort {
+    fn sort_by<F: Fn(&T, &T) -> Ordering + Sync>(&self, collection: &mut [T], compare: F) {
+        quicksort_by(collection, compare)
+    }
+}
+
+impl<T> Sorter<T> for QuickSort3Way {
+    fn sort_by<F: Fn(&T, &T) -> Ordering + Sync>(&self, collection: &mut [T], compare: F) {
+        quicksort_3way_by(collection, compare)
+    }
+}
+
+impl<T: Send> Sorter<T> for ThreadedQuickSort {
+    fn sort_by<F: Fn(&T, &T) -> Ordering + Sync>(&self, collection: &mut [T], compare: F) {
+        threaded_quicksort_by(collection, compare)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        d2_10_natural_merge_sort::NaturalMergeSort,
+        d2_11_binary_heap::{heap_sort, HeapSort},
+        d2_1_bubble_sort::bubble_sort,
+        d2_2_merge_sort::merge_sort_improved,
+        d2_2_merge_sort_source::source_merge_sort,
+        d2_3_quicksort::quicksort,
+        d2_6_instrumented_sorts::{
+            merge_sort_improved_instrumented, quicksort_instrumented, Instrument,
+        },
+        d2_7_introsort::{introsort, IntroSort},
+        helpers::float_order,
+        test_sort,
+    };
+
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    struct Record {
+        id: u32,
+        score: i32,
+    }
+
+    fn test_sorter<S: Sorter<Record> + Sorter<i32>>(sorter: S) {
+        let mut collection = vec![1273, 18273, 8273, 827, 11, 213, 2, 4, 20983, 11];
+
+        sorter.sort_by(&mut collection, |a, b| b.cmp(a));
+
+        assert_eq!(
+            collection,
+            vec![20983, 18273, 8273, 1273, 827, 213, 11, 11, 4, 2]
+        );
+
+        let mut records = vec![
+            Record { id: 1, score: 30 },
+            Record { id: 2, score: -5 },
+            Record { id: 3, score: 12 },
+            Record { id: 4, score: 0 },
+        ];
+
+        sorter.sort_by_key(&mut records, |record| record.score);
+
+        let ids = records.iter().map(|record| record.id).collect::<Vec<_>>();
+
+        assert_eq!(ids, vec![2, 4, 3, 1]);
+
+        let mut empty_collection: Vec<i32> = vec![];
+
+        sorter.sort(&mut empty_collection);
+
+        assert!(empty_collection.is_empty());
+    }
+
+    test_sort!(
+        test_sorter_sort,
+        collection,
+        QuickSort.sort(&mut collection)
+    );
+
+    #[test]
+    fn test_bubble_sorter() {
+        test_sorter(BubbleSort);
+    }
+
+    #[test]
+    fn test_merge_sorter() {
+        test_sorter(MergeSort);
+    }
+
+    #[test]
+    fn test_stable_merge_sorter() {
+        test_sorter(StableMergeSort);
+    }
+
+    #[test]
+    fn test_quick_sorter() {
+        test_sorter(QuickSort);
+    }
+
+    #[test]
+    fn test_quick_sorter_3way() {
+        test_sorter(QuickSort3Way);
+    }
+
+    #[test]
+    fn test_threaded_quick_sorter() {
+        test_sorter(ThreadedQuickSort);
+    }
+
+    #[test]
+    fn test_float_order() {
+        let (nan, inf) = (f64::NAN, f64::INFINITY);
+
+        assert_eq!(FloatOrder::Total.compare(&-nan, &-inf), Ordering::Less);
+        assert_eq!(FloatOrder::Total.compare(&-0.0, &0.0), Ordering::Less);
+        assert_eq!(FloatOrder::Total.compare(&inf, &nan), Ordering::Less);
+        assert_eq!(FloatOrder::NanFirst.compare(&nan, &-inf), Ordering::Less);
+        assert_eq!(FloatOrder::NanFirst.compare(&-nan, &nan), Ordering::Equal);
+        assert_eq!(FloatOrder::NanLast.compare(&-nan, &inf), Ordering::Greater);
+        assert_eq!(FloatOrder::NanLast.compare(&-0.0, &0.0), Ordering::Less);
+        assert_eq!(total_compare(&1.0_f32, &2.0_f32), Ordering::Less);
+
+        let mut collection = float_order::special_floats();
+        collection.sort_by(total_compare);
+        let bits = collection.iter().map(|x| x.to_bits()).collect::<Vec<_>>();
+        let mut expected = float_order::special_floats();
+        expected.sort_by(f64::total_cmp);
+        let expected_bits = expected.iter().map(|x| x.to_bits()).collect::<Vec<_>>();
+
+        assert_eq!(bits, expected_bits);
+    }
+
+    fn test_float_sorter<S: Sorter<f64>>(sorter: S) {
+        float_order::check_float_sort(|collection, order| sorter.sort_floats(collection, order));
+    }
+
+    type Floats = Vec<TotalOrder<f64>>;
+
+    // Every algorithm; the ones without a comparator sort the `TotalOrder` wrapper.
+    //
+    #[test]
+    fn test_float_sorters() {
+        test_float_sorter(BubbleSort);
+        test_float_sorter(MergeSort);
+        test_float_sorter(StableMergeSort);
+        test_float_sorter(QuickSort);
+        test_float_sorter(QuickSort3Way);
+        test_float_sorter(ThreadedQuickSort);
+        test_float_sorter(IntroSort);
+        test_float_sorter(NaturalMergeSort);
+        test_float_sorter(HeapSort);
+    }
+
+    #[test]
+    fn test_float_total_order_wrapper() {
+        let sorts: Vec<fn(Floats) -> Floats> = vec![
+            |mut collection| {
+                bubble_sort(&mut collection);
+                collection
+            },
+            |collection| merge_sort_improved(&collection),
+            source_merge_sort,
+            |mut collection| {
+                quicksort(&mut collection);
+                collection
+            },
+            |mut collection| {
+                introsort(&mut collection);
+                collection
+            },
+            |mut collection| {
+                heap_sort(&mut collection);
+                collection
+            },
+            |collection| {
+                let mut instrument = Instrument::new(false);
+                merge_sort_improved_instrumented(&collection, &mut instrument)
+            },
+            |mut collection| {
+                quicksort_instrumented(&mut collection, &mut Instrument::new(false));
+                collection
+            },
+        ];
+
+        for sort in sorts {
+            for input in float_order::float_inputs() {
+                let wrapped = input.iter().map(|x| TotalOrder(*x)).collect();
+                let output = sort(wrapped).into_iter().map(|x| x.0).collect::<Vec<_>>();
+
+                float_order::assert_float_order(&input, &output, FloatOrder::Total);
+            }
+        }
+    }
+}
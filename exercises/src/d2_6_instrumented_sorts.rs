@@ -0,0 +1,333 @@
+// Instrumented versions of the sorting algorithms of the `d2_1`-`d2_3` modules, for observing how
+// many operations they actually perform.
+//
+// The algorithms report each operation to a `SortObserver`; the plain versions pass `()`, which
+// ignores them (and is optimized away), while the instrumented ones pass an `Instrument`, so both run
+// the same code.
+//
+// The threaded quicksort is not instrumented, since it performs the same operations as the
+// sequential one, just on different threads.
+
+use crate::{
+    d2_1_bubble_sort::bubble_sort_observed,
+    d2_2_merge_sort::merge_sort_improved_observed,
+    d2_3_quicksort::{pivot_observed, quicksort_observed},
+    d2_5_sorter::partial_compare,
+};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SortStats {
+    pub comparisons: usize,
+    pub swaps: usize,
+    // Elements written to a (new) location, without swapping.
+    //
+    pub moves: usize,
+    pub allocations: usize,
+    // Maximum nesting of the (recursive) calls, including the ones on trivial (empty or single
+    // element) subcollections; the top-level call has depth 1.
+    //
+    pub recursion_depth: usize,
+}
+
+// The indices are always relative to the whole collection being sorted, so that the trace can be
+// replayed on it. In the merge sort, the compared indices refer to the sorted subcollections, as if
+// they were placed in the collection.
+//
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SortStep<T> {
+    Compare(usize, usize),
+    Swap(usize, usize),
+    Write(usize, T),
+    // The interval (`start..end`) being processed by a recursive call.
+    //
+    Enter(usize, usize),
+    Pivot(usize),
+}
+
+// Receives the operations of the sorts, before they're performed (see `SortStep`). All the methods
+// do nothing by default.
+//
+pub trait SortObserver<T> {
+    fn compare(&mut self, _i: usize, _j: usize) {}
+
+    fn swap(&mut self, _i: usize, _j: usize) {}
+
+    fn write(&mut self, _index: usize, _value: &T) {}
+
+    fn allocation(&mut self) {}
+
+    // Each `enter()` is matched by an `exit()`, when the call returns.
+    //
+    fn enter(&mut self, _start: usize, _end: usize) {}
+
+    fn exit(&mut self) {}
+
+    fn pivot(&mut self, _p: usize) {}
+}
+
+impl<T> SortObserver<T> for () {}
+
+pub struct Instrument<T> {
+    pub stats: SortStats,
+    // Recorded only if requested, since it can be very large.
+    //
+    pub trace: Option<Vec<SortStep<T>>>,
+    depth: usize,
+}
+
+impl<T> Instrument<T> {
+    pub fn new(trace: bool) -> Self {
+        Self {
+            stats: SortStats::default(),
+            trace: if trace { Some(vec![]) } else { None },
+            depth: 0,
+        }
+    }
+
+    fn record(&mut self, step: SortStep<T>) {
+        if let Some(ref mut trace) = self.trace {
+            trace.push(step);
+        }
+    }
+}
+
+impl<T: Clone> SortObserver<T> for Instrument<T> {
+    fn compare(&mut self, i: usize, j: usize) {
+        self.stats.comparisons += 1;
+        self.record(SortStep::Compare(i, j));
+    }
+
+    fn swap(&mut self, i: usize, j: usize) {
+        self.stats.swaps += 1;
+        self.record(SortStep::Swap(i, j));
+    }
+
+    fn write(&mut self, index: usize, value: &T) {
+        self.stats.moves += 1;
+        self.record(SortStep::Write(index, value.clone()));
+    }
+
+    fn allocation(&mut self) {
+        self.stats.allocations += 1;
+    }
+
+    fn enter(&mut self, start: usize, end: usize) {
+        self.depth += 1;
+        self.stats.recursion_depth = self.stats.recursion_depth.max(self.depth);
+        self.record(SortStep::Enter(start, end));
+    }
+
+    fn exit(&mut self) {
+        self.depth -= 1;
+    }
+
+    fn pivot(&mut self, p: usize) {
+        self.record(SortStep::Pivot(p));
+    }
+}
+
+pub fn bubble_sort_instrumented<T: PartialOrd + Clone>(
+    collection: &mut [T],
+    instrument: &mut Instrument<T>,
+) {
+    bubble_sort_observed(collection, partial_compare, instrument)
+}
+
+pub fn merge_sort_improved_instrumented<T: PartialOrd + Clone>(
+    collection: &[T],
+    instrument: &mut Instrument<T>,
+) -> Vec<T> {
+    merge_sort_improved_observed(collection, partial_compare, instrument)
+}
+
+pub fn pivot_instrumented<T: PartialOrd + Clone>(
+    collection: &mut [T],
+    instrument: &mut Instrument<T>,
+) -> usize {
+    pivot_observed(
+        collection,
+        partial_compare,
+        &mut rand::thread_rng(),
+        0,
+        instrument,
+    )
+}
+
+pub fn quicksort_instrumented<T: PartialOrd + Clone>(
+    collection: &mut [T],
+    instrument: &mut Instrument<T>,
+) {
+    quicksort_observed(
+        collection,
+        partial_compare,
+        &mut rand::thread_rng(),
+        instrument,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_sort;
+
+    // Replays the trace on the source collection; the result must be the sorted collection.
+    //
+    fn replay<T: Clone>(collection: &mut [T], trace: &[SortStep<T>]) {
+        for step in trace {
+            match step {
+                SortStep::Swap(i, j) => collection.swap(*i, *j),
+                SortStep::Write(i, value) => collection[*i] = value.clone(),
+                SortStep::Compare(..) | SortStep::Enter(..) | SortStep::Pivot(..) => {}
+            }
+        }
+    }
+
+    test_sort!(
+        test_bubble_sort_instrumented,
+        collection,
+        bubble_sort_instrumented(&mut collection, &mut Instrument::new(false))
+    );
+
+    test_sort!(
+        test_merge_sort_improved_instrumented,
+        collection,
+        collection = merge_sort_improved_instrumented(&collection, &mut Instrument::new(false))
+    );
+
+    test_sort!(
+        test_quicksort_instrumented,
+        collection,
+        quicksort_instrumented(&mut collection, &mut Instrument::new(false))
+    );
+
+    #[test]
+    fn test_bubble_sort_stats() {
+        // Reversed collection: worst case, all the pairs are compared and swapped.
+        //
+        let mut collection = vec![5, 4, 3, 2, 1];
+        let mut instrument = Instrument::new(false);
+
+        bubble_sort_instrumented(&mut collection, &mut instrument);
+
+        let expected_stats = SortStats {
+            comparisons: 10,
+            swaps: 10,
+            moves: 0,
+            allocations: 0,
+            recursion_depth: 1,
+        };
+
+        assert_eq!(instrument.stats, expected_stats);
+
+        // Sorted collection: a single pass.
+        //
+        let mut instrument = Instrument::new(false);
+
+        bubble_sort_instrumented(&mut collection, &mut instrument);
+
+        assert_eq!(instrument.stats.comparisons, 4);
+        assert_eq!(instrument.stats.swaps, 0);
+    }
+
+    #[test]
+    fn test_merge_sort_improved_stats() {
+        let collection = vec![8, 7, 6, 5, 4, 3, 2, 1];
+        let mut instrument = Instrument::new(false);
+
+        merge_sort_improved_instrumented(&collection, &mut instrument);
+
+        // Each of the 3 levels writes all the 8 elements; there are 8 leaves plus 7 merges. Merging
+        // two reversed halves requires only as many comparisons as the length of the second (one per
+        // merge on the last level).
+        //
+        let expected_stats = SortStats {
+            comparisons: 4 + 2 * 2 + 4,
+            swaps: 0,
+            moves: 3 * 8,
+            allocations: 8 + 7,
+            recursion_depth: 4,
+        };
+
+        assert_eq!(instrument.stats, expected_stats);
+    }
+
+    #[test]
+    fn test_pivot_stats() {
+        let mut collection = vec![3, 3, 3, 3];
+        let mut instrument = Instrument::new(true);
+
+        let p = pivot_instrumented(&mut collection, &mut instrument);
+
+        // Equal values are moved to the right of the pivot, so only the initial swap is performed.
+        //
+        assert_eq!(p, 0);
+        assert_eq!(instrument.stats.comparisons, 3);
+        assert_eq!(instrument.stats.swaps, 1);
+        assert_eq!(instrument.trace.unwrap().last(), Some(&SortStep::Pivot(0)));
+    }
+
+    #[test]
+    fn test_quicksort_stats() {
+        // With all-equal values, every partition leaves all the other elements to the right, so
+        // the recursion is as deep as the collection (the last call sorts a single element).
+        //
+        let mut collection = vec![7; 16];
+        let mut instrument = Instrument::new(false);
+
+        quicksort_instrumented(&mut collection, &mut instrument);
+
+        assert_eq!(instrument.stats.comparisons, (1..16).sum());
+        assert_eq!(instrument.stats.recursion_depth, 16);
+        assert_eq!(instrument.stats.allocations, 0);
+    }
+
+    // All the sorts count every call, including the ones on trivial subcollections.
+    //
+    #[test]
+    fn test_recursion_depth() {
+        for collection in [vec![], vec![1], vec![2, 1]].iter() {
+            let expected_depth = if collection.len() <= 1 { 1 } else { 2 };
+
+            let mut instrument = Instrument::new(false);
+            merge_sort_improved_instrumented(collection, &mut instrument);
+            assert_eq!(instrument.stats.recursion_depth, expected_depth);
+
+            let mut instrument = Instrument::new(false);
+            quicksort_instrumented(&mut collection.clone(), &mut instrument);
+            assert_eq!(instrument.stats.recursion_depth, expected_depth);
+
+            let mut instrument = Instrument::new(false);
+            bubble_sort_instrumented(&mut collection.clone(), &mut instrument);
+            assert_eq!(instrument.stats.recursion_depth, 1);
+        }
+    }
+
+    #[test]
+    fn test_traces_replay() {
+        let source_collection = vec![
+            1273, 18273, 8273, 827, 11, 213, 9172397, 2373, 2, 4, 20983, 29831093, 287, 2837, 11,
+            92900,
+        ];
+        let mut expected_collection = source_collection.clone();
+        expected_collection.sort();
+
+        let mut instrument = Instrument::new(true);
+        bubble_sort_instrumented(&mut source_collection.clone(), &mut instrument);
+        let mut collection = source_collection.clone();
+        replay(&mut collection, &instrument.trace.unwrap());
+        assert_eq!(collection, expected_collection);
+
+        let mut instrument = Instrument::new(true);
+        merge_sort_improved_instrumented(&source_collection, &mut instrument);
+        let mut collection = source_collection.clone();
+        replay(&mut collection, &instrument.trace.unwrap());
+        assert_eq!(collection, expected_collection);
+
+        let mut instrument = Instrument::new(true);
+        let mut sorted_collection = source_collection.clone();
+        quicksort_instrumented(&mut sorted_collection, &mut instrument);
+        let mut collection = source_collection.clone();
+        replay(&mut collection, &instrument.trace.unwrap());
+        assert_eq!(collection, expected_collection);
+    }
+}
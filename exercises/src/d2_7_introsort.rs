@@ -0,0 +1,157 @@
+use crate::{
+    d2_11_binary_heap::heap_sort_by,
+    d2_3_quicksort::pivot_by,
+    d2_5_sorter::{partial_compare, Sorter},
+};
+use std::cmp::Ordering;
+
+// Quicksort, whose recursion depth is tracked; when it exceeds 2*log2(n), the partitions are likely
+// unbalanced (O(n^2) worst case), so the current subcollection is heapsorted instead, which is
+// O(n*log(n)) in any case. Since the depth is bounded, so is the stack usage.
+//
+// Tiny subcollections are insertion sorted, which is faster than recursing on them.
+
+// Below this size, the subcollections are insertion sorted.
+//
+const INSERTION_SORT_MAX_LEN: usize = 16;
+
+pub fn introsort<T: PartialOrd>(collection: &mut [T]) {
+    introsort_by(collection, partial_compare)
+}
+
+pub fn introsort_by<T, F: Fn(&T, &T) -> Ordering>(collection: &mut [T], compare: F) {
+    let max_depth = 2 * log2(collection.len());
+
+    introsort_rec(collection, &compare, max_depth);
+}
+
+pub(crate) fn log2(n: usize) -> usize {
+    if n == 0 {
+        0
+    } else {
+        (usize::BITS - 1 - n.leading_zeros()) as usize
+    }
+}
+
+fn introsort_rec<T, F: Fn(&T, &T) -> Ordering>(
+    mut collection: &mut [T],
+    compare: &F,
+    mut max_depth: usize,
+) {
+    // Only the smaller partition is recursed, while the larger one is processed by the loop.
+    //
+    loop {
+        if collection.len() <= INSERTION_SORT_MAX_LEN {
+            insertion_sort_by(collection, compare);
+            return;
+        }
+
+        if max_depth == 0 {
+            heap_sort_by(collection, compare);
+            return;
+        }
+
+        max_depth -= 1;
+
+        let p = pivot_by(collection, compare);
+
+        let (collection_1, collection_2) = collection.split_at_mut(p);
+        let collection_2 = &mut collection_2[1..];
+
+        if collection_1.len() < collection_2.len() {
+            introsort_rec(collection_1, compare, max_depth);
+            collection = collection_2;
+        } else {
+            introsort_rec(collection_2, compare, max_depth);
+            collection = collection_1;
+        }
+    }
+}
+
+// Move each element to the left, until the previous one is not greater.
+//
+pub(crate) fn insertion_sort_by<T, F: Fn(&T, &T) -> Ordering>(collection: &mut [T], compare: &F) {
+    for start in 1..collection.len() {
+        let mut i = start;
+
+        while i > 0 && compare(&collection[i - 1], &collection[i]) == Ordering::Greater {
+            collection.swap(i - 1, i);
+            i -= 1;
+        }
+    }
+}
+
+pub struct IntroSort;
+
+impl<T> Sorter<T> for IntroSort {
+    fn sort_by<F: Fn(&T, &T) -> Ordering + Sync>(&self, collection: &mut [T], compare: F) {
+        introsort_by(collection, compare)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_sort, test_sort_properties};
+
+    test_sort!(test_introsort, collection, introsort(&mut collection));
+
+    test_sort!(
+        test_introsort_by,
+        collection,
+        introsort_by(&mut collection, |a, b| a.cmp(b))
+    );
+
+    test_sort!(
+        test_introsort_insertion_sort,
+        collection,
+        insertion_sort_by(&mut collection, &partial_compare)
+    );
+
+    // Forces the heapsort fallback; the collection must be larger than the insertion sort threshold.
+    //
+    #[test]
+    fn test_introsort_heap_sort_fallback() {
+        let mut collection = (0..1000).map(|_| rand::random::<u16>()).collect::<Vec<_>>();
+
+        let mut expected_collection = collection.clone();
+        expected_collection.sort();
+
+        introsort_rec(&mut collection, &partial_compare, 0);
+
+        assert_eq!(collection, expected_collection);
+    }
+
+    // All-equal values are the worst case for `pivot()`, since they're all moved to the right; the
+    // plain quicksort would recurse once per element, and overflow the stack.
+    //
+    #[test]
+    fn test_introsort_all_equal() {
+        let mut collection = vec![7; 1_000_000];
+
+        introsort(&mut collection);
+
+        assert!(collection.iter().all(|value| *value == 7));
+    }
+
+    #[test]
+    fn test_introsort_large() {
+        let mut collection = (0..100_000)
+            .map(|_| rand::random::<u16>())
+            .collect::<Vec<_>>();
+
+        let mut expected_collection = collection.clone();
+        expected_collection.sort();
+
+        IntroSort.sort_by(&mut collection, |a, b| b.cmp(a));
+        expected_collection.reverse();
+
+        assert_eq!(collection, expected_collection);
+    }
+
+    test_sort_properties!(
+        test_introsort_properties,
+        collection,
+        introsort(&mut collection)
+    );
+}
@@ -0,0 +1,229 @@
+use crate::{
+    d2_2_merge_sort::merge_sort_improved,
+    d7_44_blob_data_file::BlobError,
+    d7_45_convert_any_data_to_bytes::{read_value, write_value},
+};
+use serde::{de::DeserializeOwned, Serialize};
+use std::{
+    cmp::Reverse,
+    collections::BinaryHeap,
+    fs::File,
+    io::{BufReader, BufWriter, Seek, SeekFrom, Write},
+    iter::FusedIterator,
+};
+
+// Sorts data that doesn't fit in memory.
+//
+// The records are read in chunks of `chunk_size`; each chunk is sorted in memory, and written (as a
+// "run") to a temporary file. The runs are then merged, by keeping the current record of each of
+// them in a heap, and repeatedly taking the lowest one. Only `chunk_size` records are held in
+// memory while writing, and one per run while merging.
+//
+// The chunks are sorted with `merge_sort_improved()`, which clones the records. The temporary files
+// are deleted when the iterator is dropped.
+//
+pub fn external_sort<T, I>(records: I, chunk_size: usize) -> Result<ExternalSortIter<T>, BlobError>
+where
+    T: Serialize + DeserializeOwned + Ord + Clone,
+    I: IntoIterator<Item = T>,
+{
+    assert!(chunk_size > 0, "The chunk size must be positive");
+
+    let mut runs = vec![];
+    let mut records = records.into_iter().peekable();
+
+    while records.peek().is_some() {
+        let chunk = records.by_ref().take(chunk_size).collect::<Vec<T>>();
+
+        let chunk = merge_sort_improved(&chunk);
+
+        runs.push(Run::write(&chunk)?);
+    }
+
+    let mut heap = BinaryHeap::with_capacity(runs.len());
+
+    for (run_index, run) in runs.iter_mut().enumerate() {
+        if let Some(record) = run.next_record()? {
+            heap.push(Reverse((record, run_index)));
+        }
+    }
+
+    Ok(ExternalSortIter {
+        runs,
+        heap,
+        error: None,
+    })
+}
+
+struct Run {
+    reader: BufReader<File>,
+    remaining: usize,
+}
+
+impl Run {
+    fn write<T: Serialize>(records: &[T]) -> Result<Run, BlobError> {
+        let mut writer = BufWriter::new(tempfile::tempfile()?);
+
+        for record in records {
+            write_value(&mut writer, record)?;
+        }
+
+        writer.flush()?;
+
+        let mut file = writer.into_inner().map_err(|error| error.into_error())?;
+        file.seek(SeekFrom::Start(0))?;
+
+        Ok(Run {
+            reader: BufReader::new(file),
+            remaining: records.len(),
+        })
+    }
+
+    // The number of records is tracked, since the end of the file can't be told apart from a read
+    // error.
+    //
+    fn next_record<T: DeserializeOwned>(&mut self) -> Result<Option<T>, BlobError> {
+        if self.remaining == 0 {
+            return Ok(None);
+        }
+
+        self.remaining -= 1;
+
+        read_value(&mut self.reader).map(Some)
+    }
+}
+
+// The run index is part of the heap entries, so that, on equal records, the ones of the previous runs
+// come first, which makes the sort stable.
+//
+// When a run can't be read, the record already taken from the heap is returned first, and the error
+// on the following call; the iteration then ends, since the remaining records wouldn't be sorted.
+//
+pub struct ExternalSortIter<T> {
+    runs: Vec<Run>,
+    heap: BinaryHeap<Reverse<(T, usize)>>,
+    error: Option<BlobError>,
+}
+
+impl<T: DeserializeOwned + Ord> Iterator for ExternalSortIter<T> {
+    type Item = Result<T, BlobError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(error) = self.error.take() {
+            self.heap.clear();
+            return Some(Err(error));
+        }
+
+        let Reverse((record, run_index)) = self.heap.pop()?;
+
+        match self.runs[run_index].next_record() {
+            Ok(Some(next_record)) => self.heap.push(Reverse((next_record, run_index))),
+            Ok(None) => {}
+            Err(error) => self.error = Some(error),
+        }
+
+        Some(Ok(record))
+    }
+}
+
+impl<T: DeserializeOwned + Ord> FusedIterator for ExternalSortIter<T> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        d2_5_sorter::{FloatOrder, TotalOrder},
+        helpers::float_order,
+        test_sort_properties,
+    };
+
+    #[test]
+    fn test_external_sort() {
+        let records = (0..10_000)
+            .map(|_| rand::random::<u32>())
+            .collect::<Vec<_>>();
+
+        let mut expected_records = records.clone();
+        expected_records.sort();
+
+        let sorted_records = external_sort(records, 128)
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        assert_eq!(sorted_records, expected_records);
+    }
+
+    #[test]
+    fn test_external_sort_strings() {
+        let records = vec![
+            "pear", "apple", "fig", "kiwi", "banana", "cherry", "lime", "fig",
+        ]
+        .into_iter()
+        .map(String::from);
+
+        let sorted_records = external_sort(records, 3)
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        assert_eq!(
+            sorted_records,
+            vec!["apple", "banana", "cherry", "fig", "fig", "kiwi", "lime", "pear"]
+        );
+    }
+
+    #[test]
+    fn test_external_sort_floats() {
+        for input in float_order::float_inputs() {
+            let records = input.iter().map(|x| TotalOrder(*x)).collect::<Vec<_>>();
+
+            let sorted_records = external_sort(records, 7)
+                .unwrap()
+                .map(|record| record.map(|record| record.0))
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+
+            float_order::assert_float_order(&input, &sorted_records, FloatOrder::Total);
+        }
+    }
+
+    #[test]
+    fn test_external_sort_empty() {
+        let mut sorted_records = external_sort(Vec::<u32>::new(), 16).unwrap();
+
+        assert!(sorted_records.next().is_none());
+    }
+
+    // The records are 8 bytes each, so the truncated run holds 5000 complete records, and half of
+    // the next one.
+    //
+    #[test]
+    fn test_external_sort_truncated_run() {
+        let mut sorted_records = external_sort((0..10_000_u64).rev(), 10_000).unwrap();
+
+        sorted_records.runs[0]
+            .reader
+            .get_ref()
+            .set_len(5_000 * 8 + 4)
+            .unwrap();
+
+        for expected_record in 0..5_000 {
+            assert_eq!(sorted_records.next().unwrap().unwrap(), expected_record);
+        }
+
+        assert!(sorted_records.next().unwrap().is_err());
+        assert!(sorted_records.next().is_none());
+        assert!(sorted_records.next().is_none());
+    }
+
+    test_sort_properties!(
+        test_external_sort_properties,
+        collection,
+        collection = external_sort(collection, 7)
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap(),
+        stable = true
+    );
+}
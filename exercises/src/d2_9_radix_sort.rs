@@ -0,0 +1,507 @@
+use std::convert::TryFrom;
+
+// Non-comparison sorts: they distribute the elements in buckets, by (a part of) their key, so they're
+// O(n * key_size), rather than O(n * log(n)).
+
+// Integer keys, split in bytes. The key preserves the order of the values; for signed integers, the
+// sign bit is flipped, so that negative values come before the positive ones.
+//
+// Floats are sorted by the IEEE 754 total order (see `FloatOrder`): for the negative values, all the
+// bits are flipped, since their magnitude is reversed. Note that their keys span a wide range, so the
+// counting sort falls back to the radix sort on them (unless they're nearly all equal).
+//
+pub trait RadixKey: Copy {
+    const BYTES: usize;
+
+    fn key(&self) -> u64;
+
+    // Bytes are numbered from the least significant.
+    //
+    fn byte(&self, i: usize) -> u8 {
+        (self.key() >> (8 * i)) as u8
+    }
+}
+
+macro_rules! impl_radix_key_unsigned {
+    ($($type:ty),*) => {
+        $(
+            impl RadixKey for $type {
+                const BYTES: usize = std::mem::size_of::<$type>();
+
+                fn key(&self) -> u64 {
+                    *self as u64
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_radix_key_signed {
+    ($($type:ty => $unsigned_type:ty),*) => {
+        $(
+            impl RadixKey for $type {
+                const BYTES: usize = std::mem::size_of::<$type>();
+
+                fn key(&self) -> u64 {
+                    (*self as $unsigned_type ^ (1 << (8 * Self::BYTES - 1))) as u64
+                }
+            }
+        )*
+    };
+}
+
+impl_radix_key_unsigned!(u8, u16, u32, u64, usize);
+impl_radix_key_signed!(i8 => u8, i16 => u16, i32 => u32, i64 => u64, isize => usize);
+
+macro_rules! impl_radix_key_float {
+    ($($type:ty),*) => {
+        $(
+            impl RadixKey for $type {
+                const BYTES: usize = std::mem::size_of::<$type>();
+
+                fn key(&self) -> u64 {
+                    let bits = self.to_bits();
+                    let sign_bit = 1 << (8 * Self::BYTES - 1);
+
+                    (if bits & sign_bit == 0 { bits ^ sign_bit } else { !bits }) as u64
+                }
+            }
+        )*
+    };
+}
+
+impl_radix_key_float!(f32, f64);
+
+// Least significant digit first: the elements are distributed by each byte, from the least
+// significant; since each pass is stable, the order of the previous passes is kept for equal bytes.
+//
+// Passes where all the elements have the same byte are skipped, which is common on the high bytes of
+// small values.
+//
+pub fn radix_sort_lsd<T: RadixKey>(collection: &mut [T]) {
+    let mut buffer = collection.to_vec();
+    let mut in_buffer = false;
+
+    for i in 0..T::BYTES {
+        let sorted = if in_buffer {
+            distribute_by_byte(&buffer, collection, i)
+        } else {
+            distribute_by_byte(collection, &mut buffer, i)
+        };
+
+        if sorted {
+            in_buffer = !in_buffer;
+        }
+    }
+
+    if in_buffer {
+        collection.copy_from_slice(&buffer);
+    }
+}
+
+// Returns false if the pass has been skipped.
+//
+fn distribute_by_byte<T: RadixKey>(source: &[T], destination: &mut [T], i: usize) -> bool {
+    let mut counts = [0; 256];
+
+    for value in source {
+        counts[value.byte(i) as usize] += 1;
+    }
+
+    if counts.contains(&source.len()) {
+        return false;
+    }
+
+    let mut offsets = [0; 256];
+
+    for byte in 1..256 {
+        offsets[byte] = offsets[byte - 1] + counts[byte - 1];
+    }
+
+    for value in source {
+        let offset = &mut offsets[value.byte(i) as usize];
+        destination[*offset] = *value;
+        *offset += 1;
+    }
+
+    true
+}
+
+// Below this size, the buckets are insertion sorted.
+//
+const MSD_INSERTION_SORT_MAX_LEN: usize = 16;
+
+// Most significant digit first: the elements are distributed by their first byte, then each bucket
+// is sorted (recursively) by the following bytes. Strings ending before the current byte go to a
+// bucket before all the others.
+//
+// The distribution is performed in place (American flag sort), by swapping each element into its
+// bucket, so no buffer is needed, and any type exposing bytes (`&[u8]`, `String`...) can be sorted.
+//
+pub fn radix_sort_msd<T: AsRef<[u8]>>(collection: &mut [T]) {
+    radix_sort_msd_rec(collection, 0);
+}
+
+// Only the smaller buckets are sorted recursively; the largest one is sorted by the loop, so that the
+// recursion depth is O(log(n)), regardless of the length of the keys (e.g. long common prefixes).
+//
+fn radix_sort_msd_rec<T: AsRef<[u8]>>(mut collection: &mut [T], mut depth: usize) {
+    loop {
+        if collection.len() <= MSD_INSERTION_SORT_MAX_LEN {
+            for start in 1..collection.len() {
+                let mut i = start;
+
+                while i > 0 && collection[i - 1].as_ref()[depth..] > collection[i].as_ref()[depth..]
+                {
+                    collection.swap(i - 1, i);
+                    i -= 1;
+                }
+            }
+
+            return;
+        }
+
+        let bucket = |value: &T| {
+            value
+                .as_ref()
+                .get(depth)
+                .map_or(0, |byte| *byte as usize + 1)
+        };
+
+        let mut counts = [0; 257];
+
+        for value in collection.iter() {
+            counts[bucket(value)] += 1;
+        }
+
+        // All the elements have the same byte; there's nothing to distribute. If they're all ended,
+        // they're all equal.
+        //
+        if counts[0] == collection.len() {
+            return;
+        } else if counts.contains(&collection.len()) {
+            depth += 1;
+            continue;
+        }
+
+        let mut starts = [0; 257];
+
+        for i in 1..257 {
+            starts[i] = starts[i - 1] + counts[i - 1];
+        }
+
+        // `next[b]` is the first position of the bucket `b` not holding an element of it yet.
+        //
+        let mut next = starts;
+
+        for b in 0..257 {
+            let end = starts[b] + counts[b];
+
+            while next[b] < end {
+                let target_b = bucket(&collection[next[b]]);
+
+                if target_b == b {
+                    next[b] += 1;
+                } else {
+                    collection.swap(next[b], next[target_b]);
+                    next[target_b] += 1;
+                }
+            }
+        }
+
+        // The first bucket holds the ended strings, which are all equal.
+        //
+        let largest_b = (1..257).max_by_key(|b| counts[*b]).unwrap();
+
+        for b in (1..257).filter(|b| *b != largest_b) {
+            let start = starts[b];
+            radix_sort_msd_rec(&mut collection[start..start + counts[b]], depth + 1);
+        }
+
+        let start = starts[largest_b];
+        collection = &mut collection[start..start + counts[largest_b]];
+        depth += 1;
+    }
+}
+
+// Above this ratio of the keys range to the collection length, the counts would take more memory
+// (and time) than the elements, so the counting sort falls back to the LSD radix sort. Ranges up to
+// a byte are always counted, as a radix sort pass would use as many counts anyway.
+//
+const COUNTING_SORT_MAX_RANGE_RATIO: usize = 4;
+const COUNTING_SORT_MIN_MAX_RANGE: usize = 256;
+
+// Counts the occurrences of each key, then rewrites the collection in order. The memory used is
+// proportional to the range of the keys (max - min), so it's suitable only for small ranges; on
+// larger ones, the LSD radix sort is used.
+//
+pub fn counting_sort<T: RadixKey>(collection: &mut [T]) {
+    let (min, max) = match (
+        collection.iter().map(RadixKey::key).min(),
+        collection.iter().map(RadixKey::key).max(),
+    ) {
+        (Some(min), Some(max)) => (min, max),
+        _ => return,
+    };
+
+    let max_range = collection
+        .len()
+        .saturating_mul(COUNTING_SORT_MAX_RANGE_RATIO)
+        .max(COUNTING_SORT_MIN_MAX_RANGE);
+
+    // The range (number of keys) doesn't fit a `u64`/`usize` when the keys span all of them.
+    //
+    let range = max
+        .checked_sub(min)
+        .and_then(|difference| usize::try_from(difference).ok())
+        .and_then(|difference| difference.checked_add(1));
+
+    let range = match range {
+        Some(range) if range <= max_range => range,
+        _ => return radix_sort_lsd(collection),
+    };
+
+    let mut counts = vec![0; range];
+
+    for value in collection.iter() {
+        counts[(value.key() - min) as usize] += 1;
+    }
+
+    for i in 1..counts.len() {
+        counts[i] += counts[i - 1];
+    }
+
+    // Filling from the end, with the counts as (exclusive) end positions, keeps the sort stable.
+    //
+    let source_collection = collection.to_vec();
+
+    for value in source_collection.iter().rev() {
+        let position = &mut counts[(value.key() - min) as usize];
+        *position -= 1;
+        collection[*position] = *value;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{d2_5_sorter::FloatOrder, helpers::float_order, test_sort, test_sort_properties};
+
+    test_sort!(
+        test_radix_sort_lsd,
+        collection,
+        radix_sort_lsd(&mut collection)
+    );
+
+    #[test]
+    fn test_radix_sort_lsd_types() {
+        let mut collection = (0..100_000)
+            .map(|_| rand::random::<u64>())
+            .collect::<Vec<_>>();
+        let mut expected_collection = collection.clone();
+        expected_collection.sort();
+
+        radix_sort_lsd(&mut collection);
+
+        assert_eq!(collection, expected_collection);
+
+        let mut collection = vec![3_i64, -1, i64::MIN, 0, i64::MAX, -200, 7];
+
+        radix_sort_lsd(&mut collection);
+
+        assert_eq!(collection, vec![i64::MIN, -200, -1, 0, 3, 7, i64::MAX]);
+    }
+
+    #[test]
+    fn test_radix_sort_lsd_floats() {
+        for input in float_order::float_inputs() {
+            let mut collection = input.clone();
+
+            radix_sort_lsd(&mut collection);
+
+            float_order::assert_float_order(&input, &collection, FloatOrder::Total);
+        }
+
+        let mut collection = vec![1.5_f32, -0.0, f32::NAN, f32::NEG_INFINITY, 0.0, -2.5];
+
+        radix_sort_lsd(&mut collection);
+
+        let bits = collection.iter().map(|x| x.to_bits()).collect::<Vec<_>>();
+        let expected_bits = [f32::NEG_INFINITY, -2.5, -0.0, 0.0, 1.5, f32::NAN]
+            .iter()
+            .map(|x| x.to_bits())
+            .collect::<Vec<_>>();
+
+        assert_eq!(bits, expected_bits);
+    }
+
+    #[test]
+    fn test_counting_sort_floats() {
+        for input in float_order::float_inputs() {
+            let mut collection = input.clone();
+
+            counting_sort(&mut collection);
+
+            float_order::assert_float_order(&input, &collection, FloatOrder::Total);
+        }
+
+        let mut collection = vec![1.0_f64, -1.0];
+
+        counting_sort(&mut collection);
+
+        assert_eq!(collection, vec![-1.0, 1.0]);
+    }
+
+    #[test]
+    fn test_radix_sort_msd() {
+        let mut collection = vec![
+            "pear",
+            "apple",
+            "fig",
+            "",
+            "kiwi",
+            "banana",
+            "cherry",
+            "lime",
+            "date",
+            "grape",
+            "applesauce",
+            "app",
+            "figs",
+            "banana",
+            "ape",
+            "cherries",
+            "a",
+            "kiwis",
+            "pea",
+            "peach",
+        ];
+
+        let mut expected_collection = collection.clone();
+        expected_collection.sort();
+
+        radix_sort_msd(&mut collection);
+
+        assert_eq!(collection, expected_collection);
+    }
+
+    #[test]
+    fn test_radix_sort_msd_large() {
+        let mut collection = (0..10_000)
+            .map(|_| {
+                let len = rand::random::<usize>() % 8;
+                (0..len)
+                    .map(|_| rand::random::<u8>() % 4)
+                    .collect::<Vec<u8>>()
+            })
+            .collect::<Vec<_>>();
+
+        let mut expected_collection = collection.clone();
+        expected_collection.sort();
+
+        radix_sort_msd(&mut collection);
+
+        assert_eq!(collection, expected_collection);
+    }
+
+    // The big endian bytes of non-negative integers have the same order as the integers.
+    //
+    test_sort!(test_radix_sort_msd_keys, collection, {
+        let mut keys = collection
+            .iter()
+            .map(|value| i32::to_be_bytes(*value))
+            .collect::<Vec<_>>();
+
+        radix_sort_msd(&mut keys);
+
+        collection = keys.into_iter().map(i32::from_be_bytes).collect();
+    });
+
+    // The range of these values is wide, so this exercises the fallback.
+    //
+    test_sort!(
+        test_counting_sort_keys,
+        collection,
+        counting_sort(&mut collection)
+    );
+
+    // Long common prefixes don't increase the recursion depth; each level would otherwise take a
+    // stack frame, and overflow the (2 MB) test thread stack.
+    //
+    #[test]
+    fn test_radix_sort_msd_long_prefixes() {
+        let mut collection = vec![vec![7_u8; 10_000]; 17];
+
+        radix_sort_msd(&mut collection);
+
+        assert!(collection.iter().all(|value| *value == vec![7_u8; 10_000]));
+
+        let mut collection = (1..2_000).map(|len| "a".repeat(len)).collect::<Vec<_>>();
+        collection.reverse();
+        collection.push("b".to_string());
+
+        let mut expected_collection = collection.clone();
+        expected_collection.sort();
+
+        radix_sort_msd(&mut collection);
+
+        assert_eq!(collection, expected_collection);
+    }
+
+    #[test]
+    fn test_counting_sort() {
+        let mut collection = (0..10_000)
+            .map(|_| rand::random::<i16>() % 100)
+            .collect::<Vec<_>>();
+        let mut expected_collection = collection.clone();
+        expected_collection.sort();
+
+        counting_sort(&mut collection);
+
+        assert_eq!(collection, expected_collection);
+
+        let mut empty_collection: Vec<u8> = vec![];
+
+        counting_sort(&mut empty_collection);
+
+        assert!(empty_collection.is_empty());
+    }
+
+    // Keys spanning the whole range, or a range much larger than the collection, fall back to the
+    // radix sort, rather than allocating the counts.
+    //
+    #[test]
+    fn test_counting_sort_wide_range() {
+        let mut collection = vec![u64::MAX, 0, u64::MAX - 1, 1, 0];
+
+        counting_sort(&mut collection);
+
+        assert_eq!(collection, vec![0, 0, 1, u64::MAX - 1, u64::MAX]);
+
+        let mut collection = vec![i64::MAX, i64::MIN, -1, 0];
+
+        counting_sort(&mut collection);
+
+        assert_eq!(collection, vec![i64::MIN, -1, 0, i64::MAX]);
+
+        let mut collection = vec![1_000_000_000_u32, 3, 1_000_000_000, 2];
+
+        counting_sort(&mut collection);
+
+        assert_eq!(collection, vec![2, 3, 1_000_000_000, 1_000_000_000]);
+    }
+
+    test_sort_properties!(
+        test_radix_sort_lsd_properties,
+        collection,
+        radix_sort_lsd(&mut collection),
+        stable = true
+    );
+
+    test_sort_properties!(
+        test_counting_sort_properties,
+        collection,
+        counting_sort(&mut collection),
+        stable = true
+    );
+}
@@ -1,9 +1,11 @@
-// PartialOrd is for the exercise.
-// Copy is for the list values function.
+// Each node owns the rest of the list; the last node is followed by an empty list.
 //
-pub struct LinkedList<T: PartialOrd + Copy>(Option<(T, Box<LinkedList<T>>)>);
+// The bounds are on the methods requiring them: PartialOrd is for the exercise, and Clone for the
+// list values function.
+//
+pub struct LinkedList<T>(Option<(T, Box<LinkedList<T>>)>);
 
-impl<T: PartialOrd + Copy> LinkedList<T> {
+impl<T> LinkedList<T> {
     pub fn new() -> LinkedList<T> {
         LinkedList(None)
     }
@@ -14,22 +16,129 @@ impl<T: PartialOrd + Copy> LinkedList<T> {
     }
 
     pub fn push_back_iterative(&mut self, data: T) {
+        self.tail().push_front(data);
+    }
+
+    pub fn push_back_recursive(&mut self, data: T) {
+        match self.0 {
+            Some((_, ref mut child)) => child.push_back_recursive(data),
+            None => self.push_front(data),
+        }
+    }
+
+    // The empty list at the end.
+    //
+    fn tail(&mut self) -> &mut LinkedList<T> {
         let mut current = self;
 
         while let Some((_, ref mut child)) = current.0 {
             current = child;
         }
 
-        current.push_front(data);
+        current
     }
 
-    pub fn push_back_recursive(&mut self, data: T) {
-        match self.0 {
-            Some((_, ref mut child)) => child.push_back_recursive(data),
-            None => self.push_front(data),
+    // The struct can't be destructured (since it implements Drop), so the child is moved as a whole.
+    //
+    pub fn pop_front(&mut self) -> Option<T> {
+        let (data, child) = self.0.take()?;
+        *self = *child;
+        Some(data)
+    }
+
+    pub fn pop_back(&mut self) -> Option<T> {
+        let mut current = self;
+
+        // Stops at the last node (or at the empty list); see `sorted_push_iterative()` for the split
+        // arms.
+        //
+        loop {
+            match current.0 {
+                Some((_, ref child)) if child.0.is_none() => break,
+                Some((_, ref mut child)) => current = child,
+                None => break,
+            }
+        }
+
+        current.pop_front()
+    }
+
+    pub fn peek(&self) -> Option<&T> {
+        self.0.as_ref().map(|(data, _)| data)
+    }
+
+    pub fn peek_mut(&mut self) -> Option<&mut T> {
+        self.0.as_mut().map(|(data, _)| data)
+    }
+
+    // The length is not stored, so this is O(n).
+    //
+    pub fn len(&self) -> usize {
+        self.iter().count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_none()
+    }
+
+    // The nodes are relinked, rather than popped and pushed, so no allocations are performed.
+    //
+    pub fn reverse(&mut self) {
+        let mut reversed = None;
+        let mut current = self.0.take();
+
+        while let Some((data, mut child)) = current {
+            current = child.0.take();
+            child.0 = reversed;
+            reversed = Some((data, child));
+        }
+
+        self.0 = reversed;
+    }
+
+    // Returns None if the index is out of range.
+    //
+    pub fn remove(&mut self, index: usize) -> Option<T> {
+        let mut current = self;
+
+        for _ in 0..index {
+            match current.0 {
+                Some((_, ref mut child)) => current = child,
+                None => return None,
+            }
         }
+
+        current.pop_front()
     }
 
+    // Removes the values for which the predicate returns false.
+    //
+    pub fn retain<F: FnMut(&T) -> bool>(&mut self, mut predicate: F) {
+        let mut current = self;
+
+        loop {
+            match current.0 {
+                Some((ref data, _)) if !predicate(data) => {
+                    current.pop_front();
+                }
+                Some((_, ref mut child)) => current = child,
+                None => break,
+            }
+        }
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter { current: self }
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut {
+            current: Some(self),
+        }
+    }
+}
+
+impl<T: PartialOrd> LinkedList<T> {
     // Exercise
     //
     pub fn sorted_push_iterative(&mut self, value: T) {
@@ -53,18 +162,116 @@ impl<T: PartialOrd + Copy> LinkedList<T> {
         }
         current.push_front(value);
     }
+}
 
+impl<T: Clone> LinkedList<T> {
     pub fn values(&self) -> Vec<T> {
-        let mut values = vec![];
+        self.iter().cloned().collect()
+    }
+}
 
-        let mut current = self;
+impl<T> Default for LinkedList<T> {
+    fn default() -> Self {
+        LinkedList::new()
+    }
+}
 
-        while let Some((ref child_data, ref child)) = current.0 {
-            values.push(*child_data);
-            current = child;
+// The default drop is recursive (each node drops its child), which overflows the stack on long
+// lists; the nodes are instead unlinked and dropped one at a time.
+//
+impl<T> Drop for LinkedList<T> {
+    fn drop(&mut self) {
+        let mut current = self.0.take();
+
+        while let Some((_, mut child)) = current {
+            current = child.0.take();
+        }
+    }
+}
+
+pub struct Iter<'a, T> {
+    current: &'a LinkedList<T>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (data, child) = self.current.0.as_ref()?;
+        self.current = child;
+        Some(data)
+    }
+}
+
+pub struct IterMut<'a, T> {
+    current: Option<&'a mut LinkedList<T>>,
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (data, child) = self.current.take()?.0.as_mut()?;
+        self.current = Some(child);
+        Some(data)
+    }
+}
+
+pub struct IntoIter<T>(LinkedList<T>);
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.pop_front()
+    }
+}
+
+impl<T> IntoIterator for LinkedList<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter(self)
+    }
+}
+
+impl<'a, T> IntoIterator for &'a LinkedList<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut LinkedList<T> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+// The values are appended; the tail is tracked, so that each push is O(1).
+//
+impl<T> Extend<T> for LinkedList<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, values: I) {
+        let mut tail = self.tail();
+
+        for value in values {
+            tail.push_front(value);
+            tail = tail.tail();
         }
+    }
+}
 
-        values
+impl<T> std::iter::FromIterator<T> for LinkedList<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(values: I) -> Self {
+        let mut list = LinkedList::new();
+        list.extend(values);
+        list
     }
 }
 
@@ -106,4 +313,127 @@ mod tests {
 
         assert_eq!(list.values()[..], sorted_values[..]);
     }
+
+    #[test]
+    fn test_pop() {
+        let mut list = (1..=3).collect::<LinkedList<_>>();
+
+        assert_eq!(list.pop_back(), Some(3));
+        assert_eq!(list.pop_front(), Some(1));
+        assert_eq!(list.peek(), Some(&2));
+        assert_eq!(list.pop_back(), Some(2));
+        assert_eq!(list.pop_back(), None);
+        assert_eq!(list.pop_front(), None);
+        assert_eq!(list.peek(), None);
+        assert!(list.is_empty());
+        assert_eq!(list.len(), 0);
+    }
+
+    #[test]
+    fn test_peek_mut() {
+        let mut list = LinkedList::new();
+        list.push_front(1);
+
+        *list.peek_mut().unwrap() += 10;
+
+        assert_eq!(list.values(), vec![11]);
+    }
+
+    #[test]
+    fn test_reverse() {
+        let mut list = (0..10).collect::<LinkedList<_>>();
+
+        list.reverse();
+
+        assert_eq!(list.values(), (0..10).rev().collect::<Vec<_>>());
+        assert_eq!(list.len(), 10);
+
+        let mut empty_list = LinkedList::<i32>::new();
+        empty_list.reverse();
+
+        assert!(empty_list.is_empty());
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut list = (0..5).collect::<LinkedList<_>>();
+
+        assert_eq!(list.remove(5), None);
+        assert_eq!(list.remove(4), Some(4));
+        assert_eq!(list.remove(0), Some(0));
+        assert_eq!(list.remove(1), Some(2));
+        assert_eq!(list.values(), vec![1, 3]);
+    }
+
+    #[test]
+    fn test_retain() {
+        let mut list = vec![1, 2, 2, 3, 4, 4, 5, 6]
+            .into_iter()
+            .collect::<LinkedList<_>>();
+
+        list.retain(|value| value % 2 == 1);
+
+        assert_eq!(list.values(), vec![1, 3, 5]);
+
+        list.retain(|_| false);
+
+        assert!(list.is_empty());
+    }
+
+    // Values that are not `Copy`.
+    //
+    #[test]
+    fn test_iterators() {
+        let mut list = vec!["a", "b", "c"]
+            .into_iter()
+            .map(String::from)
+            .collect::<LinkedList<_>>();
+
+        for value in list.iter_mut() {
+            value.push('!');
+        }
+
+        for value in &mut list {
+            value.push('?');
+        }
+
+        list.extend(vec!["d".to_string()]);
+
+        assert_eq!(
+            list.iter().map(String::as_str).collect::<Vec<_>>(),
+            vec!["a!?", "b!?", "c!?", "d"]
+        );
+        assert_eq!((&list).into_iter().count(), 4);
+        assert_eq!(
+            list.into_iter().collect::<Vec<_>>(),
+            vec!["a!?", "b!?", "c!?", "d"]
+        );
+    }
+
+    #[test]
+    fn test_extend() {
+        let mut list = LinkedList::new();
+
+        list.extend(0..3);
+        list.push_back_recursive(3);
+        list.extend(4..6);
+
+        assert_eq!(list.values(), (0..6).collect::<Vec<_>>());
+    }
+
+    // A recursive drop (or build) would overflow the stack.
+    //
+    #[test]
+    fn test_long_list() {
+        let mut list = (0..1_000_000).collect::<LinkedList<_>>();
+
+        assert_eq!(list.len(), 1_000_000);
+
+        list.reverse();
+
+        assert_eq!(list.peek(), Some(&999_999));
+        assert_eq!(list.pop_back(), Some(0));
+
+        drop(list);
+    }
 }
@@ -133,6 +133,231 @@ impl<T: Copy> LinkedList<T> {
     }
 }
 
+impl<T: Copy> LinkedList<T> {
+    pub fn cursor_front_mut(&mut self) -> CursorMut<'_, T> {
+        let current = self.front();
+        CursorMut {
+            list: self,
+            current,
+        }
+    }
+
+    pub fn cursor_back_mut(&mut self) -> CursorMut<'_, T> {
+        let current = self.back();
+        CursorMut {
+            list: self,
+            current,
+        }
+    }
+
+    fn front(&self) -> Option<StrongNodeRef<T>> {
+        self.0.as_ref().map(|(front, _)| Rc::clone(front))
+    }
+
+    fn back(&self) -> Option<StrongNodeRef<T>> {
+        self.0
+            .as_ref()
+            .map(|(_, back)| Weak::upgrade(back).unwrap())
+    }
+
+    // The ends setters require a non-empty list.
+    //
+    fn set_front(&mut self, node: StrongNodeRef<T>) {
+        if let Some((ref mut front, _)) = self.0 {
+            *front = node;
+        }
+    }
+
+    fn set_back(&mut self, node: &StrongNodeRef<T>) {
+        if let Some((_, ref mut back)) = self.0 {
+            *back = Rc::downgrade(node);
+        }
+    }
+}
+
+// Cursor for editing the list in the middle. It points to a node, or to the "ghost" position
+// (`None`), which is between the back and the front: moving past an end reaches it, and moving from
+// it continues from the other end. On the ghost, the operations "after" act on the front, and the
+// operations "before" on the back.
+//
+// The cursor holds a strong reference to the current node, and the list mutable reference, so that
+// the ends can be updated when the nodes around them change.
+//
+pub struct CursorMut<'a, T: Copy> {
+    list: &'a mut LinkedList<T>,
+    current: Option<StrongNodeRef<T>>,
+}
+
+impl<'a, T: Copy> CursorMut<'a, T> {
+    pub fn current(&self) -> Option<T> {
+        self.current.as_ref().map(|node| node.borrow().value)
+    }
+
+    // Returns the previous value; does nothing on the ghost.
+    //
+    pub fn set_current(&mut self, value: T) -> Option<T> {
+        self.current
+            .as_ref()
+            .map(|node| std::mem::replace(&mut node.borrow_mut().value, value))
+    }
+
+    pub fn move_next(&mut self) {
+        let next = match self.current {
+            Some(ref node) => node.borrow().next.as_ref().map(Rc::clone),
+            None => self.list.front(),
+        };
+
+        self.current = next;
+    }
+
+    pub fn move_prev(&mut self) {
+        let previous = match self.current {
+            Some(ref node) => node
+                .borrow()
+                .previous
+                .as_ref()
+                .map(|previous| Weak::upgrade(previous).unwrap()),
+            None => self.list.back(),
+        };
+
+        self.current = previous;
+    }
+
+    pub fn insert_after(&mut self, value: T) {
+        let node = match self.current {
+            Some(ref node) => node,
+            None => return self.list.push_front(value),
+        };
+
+        let next = node.borrow_mut().next.take();
+
+        let new_node = Rc::new(RefCell::new(Node {
+            value,
+            next: next.as_ref().map(Rc::clone),
+            previous: Some(Rc::downgrade(node)),
+        }));
+
+        match next {
+            Some(next) => next.borrow_mut().previous = Some(Rc::downgrade(&new_node)),
+            None => self.list.set_back(&new_node),
+        }
+
+        node.borrow_mut().next = Some(new_node);
+    }
+
+    pub fn insert_before(&mut self, value: T) {
+        let node = match self.current {
+            Some(ref node) => node,
+            None => return self.list.push_back(value),
+        };
+
+        let previous = node.borrow_mut().previous.take();
+
+        let new_node = Rc::new(RefCell::new(Node {
+            value,
+            next: Some(Rc::clone(node)),
+            previous: previous.as_ref().map(Weak::clone),
+        }));
+
+        node.borrow_mut().previous = Some(Rc::downgrade(&new_node));
+
+        match previous {
+            Some(previous) => Weak::upgrade(&previous).unwrap().borrow_mut().next = Some(new_node),
+            None => self.list.set_front(new_node),
+        }
+    }
+
+    // Moves to the next node (or to the ghost, if the current node was the back).
+    //
+    pub fn remove_current(&mut self) -> Option<T> {
+        let node = self.current.take()?;
+
+        let (previous, next) = {
+            let mut node = node.borrow_mut();
+            let previous = node
+                .previous
+                .take()
+                .map(|previous| Weak::upgrade(&previous).unwrap());
+            (previous, node.next.take())
+        };
+
+        match (previous, next.as_ref()) {
+            (None, None) => self.list.0 = None,
+            (Some(previous), None) => {
+                previous.borrow_mut().next = None;
+                self.list.set_back(&previous);
+            }
+            (None, Some(next)) => {
+                next.borrow_mut().previous = None;
+                self.list.set_front(Rc::clone(next));
+            }
+            (Some(previous), Some(next)) => {
+                next.borrow_mut().previous = Some(Rc::downgrade(&previous));
+                previous.borrow_mut().next = Some(Rc::clone(next));
+            }
+        }
+
+        self.current = next;
+
+        let value = node.borrow().value;
+        Some(value)
+    }
+
+    // Returns the nodes after the current one as a new list; on the ghost, the whole list is moved.
+    //
+    pub fn split_after(&mut self) -> LinkedList<T> {
+        let node = match self.current {
+            Some(ref node) => node,
+            None => return std::mem::replace(self.list, LinkedList::new()),
+        };
+
+        let next = match node.borrow_mut().next.take() {
+            Some(next) => next,
+            None => return LinkedList::new(),
+        };
+
+        next.borrow_mut().previous = None;
+
+        let back = self.list.back().unwrap();
+        self.list.set_back(node);
+
+        LinkedList(Some((next, Rc::downgrade(&back))))
+    }
+
+    // Inserts the other list after the current node, in O(1); on the ghost, at the front.
+    //
+    pub fn splice_after(&mut self, other: LinkedList<T>) {
+        let (other_front, other_back) = match other.0 {
+            Some((front, back)) => (front, Weak::upgrade(&back).unwrap()),
+            None => return,
+        };
+
+        let next = match self.current {
+            Some(ref node) => {
+                other_front.borrow_mut().previous = Some(Rc::downgrade(node));
+                node.borrow_mut().next.replace(Rc::clone(&other_front))
+            }
+            None => {
+                let front = self.list.front();
+                if front.is_none() {
+                    self.list.0 = Some((other_front, Rc::downgrade(&other_back)));
+                } else {
+                    self.list.set_front(other_front);
+                }
+                front
+            }
+        };
+
+        match next {
+            Some(next) => {
+                next.borrow_mut().previous = Some(Rc::downgrade(&other_back));
+                other_back.borrow_mut().next = Some(next);
+            }
+            None => self.list.set_back(&other_back),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -216,4 +441,190 @@ mod tests {
 
         assert!(list.pop_back().is_none());
     }
+
+    // Checks the values from both ends, following the `next` and the `previous` references, so that
+    // all the links (and the ends) are verified.
+    //
+    fn assert_links(list: &LinkedList<i32>, expected_values: &[i32]) {
+        assert_eq!(list.values()[..], expected_values[..]);
+
+        let mut values = vec![];
+        let mut current = list.back();
+
+        while let Some(node) = current {
+            values.push(node.borrow().value);
+            current = node
+                .borrow()
+                .previous
+                .as_ref()
+                .map(|previous| Weak::upgrade(previous).unwrap());
+        }
+
+        values.reverse();
+
+        assert_eq!(values[..], expected_values[..]);
+    }
+
+    fn list_of(values: &[i32]) -> LinkedList<i32> {
+        let mut list = LinkedList::new();
+
+        for value in values {
+            list.push_back(*value);
+        }
+
+        list
+    }
+
+    #[test]
+    fn test_cursor_move() {
+        let mut list = list_of(&[1, 2, 3]);
+        let mut cursor = list.cursor_front_mut();
+
+        assert_eq!(cursor.current(), Some(1));
+
+        cursor.move_next();
+        cursor.move_next();
+        assert_eq!(cursor.current(), Some(3));
+
+        // Ghost, then wrap around.
+        //
+        cursor.move_next();
+        assert_eq!(cursor.current(), None);
+        cursor.move_next();
+        assert_eq!(cursor.current(), Some(1));
+
+        cursor.move_prev();
+        assert_eq!(cursor.current(), None);
+        cursor.move_prev();
+        assert_eq!(cursor.current(), Some(3));
+
+        assert_eq!(cursor.set_current(30), Some(3));
+
+        assert_eq!(list.cursor_back_mut().current(), Some(30));
+        assert_eq!(LinkedList::<i32>::new().cursor_front_mut().current(), None);
+    }
+
+    #[test]
+    fn test_cursor_insert() {
+        let mut list = list_of(&[2, 4]);
+        let mut cursor = list.cursor_front_mut();
+
+        cursor.insert_before(1);
+        cursor.insert_after(3);
+        cursor.move_next();
+        cursor.move_next();
+        cursor.insert_after(5);
+
+        // On the ghost, before is the back, and after is the front.
+        //
+        cursor.move_next();
+        cursor.move_next();
+        assert_eq!(cursor.current(), None);
+        cursor.insert_before(6);
+        cursor.insert_after(0);
+
+        assert_links(&list, &[0, 1, 2, 3, 4, 5, 6]);
+
+        let mut empty_list = LinkedList::new();
+        empty_list.cursor_front_mut().insert_after(1);
+
+        assert_links(&empty_list, &[1]);
+    }
+
+    #[test]
+    fn test_cursor_remove() {
+        let mut list = list_of(&[1, 2, 3, 4]);
+        let mut cursor = list.cursor_front_mut();
+
+        // Front.
+        //
+        assert_eq!(cursor.remove_current(), Some(1));
+        assert_eq!(cursor.current(), Some(2));
+
+        // Middle.
+        //
+        cursor.move_next();
+        assert_eq!(cursor.remove_current(), Some(3));
+        assert_eq!(cursor.current(), Some(4));
+
+        // Back.
+        //
+        assert_eq!(cursor.remove_current(), Some(4));
+        assert_eq!(cursor.current(), None);
+        assert_eq!(cursor.remove_current(), None);
+
+        assert_links(&list, &[2]);
+
+        let mut cursor = list.cursor_back_mut();
+
+        assert_eq!(cursor.remove_current(), Some(2));
+
+        assert!(list.0.is_none());
+    }
+
+    #[test]
+    fn test_cursor_split_after() {
+        let mut list = list_of(&[1, 2, 3, 4]);
+        let mut cursor = list.cursor_front_mut();
+
+        cursor.move_next();
+        let tail = cursor.split_after();
+
+        assert_links(&list, &[1, 2]);
+        assert_links(&tail, &[3, 4]);
+
+        let mut cursor = list.cursor_back_mut();
+
+        assert!(cursor.split_after().0.is_none());
+
+        cursor.move_next();
+        let whole = cursor.split_after();
+
+        assert!(list.0.is_none());
+        assert_links(&whole, &[1, 2]);
+    }
+
+    #[test]
+    fn test_cursor_splice_after() {
+        let mut list = list_of(&[1, 5]);
+        let mut cursor = list.cursor_front_mut();
+
+        cursor.splice_after(list_of(&[2, 3, 4]));
+        cursor.splice_after(LinkedList::new());
+
+        // On the ghost, at the front.
+        //
+        cursor.move_prev();
+        cursor.splice_after(list_of(&[-1, 0]));
+        cursor.move_prev();
+        cursor.splice_after(list_of(&[6, 7]));
+
+        assert_links(&list, &[-1, 0, 1, 2, 3, 4, 5, 6, 7]);
+
+        assert_eq!(list.cursor_back_mut().current(), Some(7));
+
+        let mut empty_list = LinkedList::new();
+        empty_list.cursor_front_mut().splice_after(list_of(&[1, 2]));
+
+        assert_links(&empty_list, &[1, 2]);
+    }
+
+    // Split and splice are inverse.
+    //
+    #[test]
+    fn test_cursor_split_splice() {
+        let mut list = list_of(&[1, 2, 3, 4, 5]);
+        let mut cursor = list.cursor_front_mut();
+
+        cursor.move_next();
+        cursor.move_next();
+        let tail = cursor.split_after();
+        cursor.move_prev();
+        cursor.insert_after(10);
+        cursor.move_next();
+        cursor.move_next();
+        cursor.splice_after(tail);
+
+        assert_links(&list, &[1, 2, 10, 3, 4, 5]);
+    }
 }
@@ -47,3 +47,355 @@ macro_rules! test_sort_refs {
       }
   };
 }
+
+// Randomized version of `test_sort!`: the statement is run on many generated collections of
+// `helpers::sort_properties::Record`, and the result is checked to be an ordered permutation of the
+// input (and, optionally, to preserve the order of equal records). Failing inputs are shrunk, and
+// printed along with the seed, which can be set via the `SORT_CHECK_SEED` env variable.
+//
+// Options are passed as `name = value` (see `SortCheck`), e.g.:
+//
+//   test_sort_properties!(test_name, collection, my_sort(&mut collection), stable = true);
+//
+#[macro_export]
+macro_rules! test_sort_properties {
+  ($test_name:ident, $collection:ident, $stat:stmt $(, $option:ident = $value:expr)*) => {
+      #[test]
+      fn $test_name() {
+          #[allow(unused_mut)]
+          let mut sort_check = $crate::helpers::sort_properties::SortCheck::default();
+          $(sort_check.$option = $value;)*
+
+          sort_check.run(|mut $collection: Vec<$crate::helpers::sort_properties::Record>| {
+              $stat
+              $collection
+          });
+      }
+  };
+}
+
+#[cfg(test)]
+pub mod sort_properties {
+    use crate::d2_9_radix_sort::RadixKey;
+    use serde_derive::{Deserialize, Serialize};
+    use std::{
+        cmp::Ordering,
+        panic::{self, AssertUnwindSafe},
+    };
+
+    // Keys are kept in a small range, so that there are duplicates (which are required to check the
+    // stability), and that sorts whose memory is proportional to the range can be tested.
+    //
+    const KEY_RANGE: std::ops::RangeInclusive<i32> = -1000..=1000;
+
+    // Max attempts to shrink a failing input; each one runs the sort.
+    //
+    const MAX_SHRINK_ATTEMPTS: usize = 10_000;
+
+    // Records are compared by key only; the id is the position in the input, so that the order of
+    // equal records can be checked.
+    //
+    #[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+    pub struct Record {
+        pub key: i32,
+        pub id: usize,
+    }
+
+    impl PartialEq for Record {
+        fn eq(&self, other: &Self) -> bool {
+            self.key == other.key
+        }
+    }
+
+    impl Eq for Record {}
+
+    impl PartialOrd for Record {
+        fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+            Some(self.cmp(other))
+        }
+    }
+
+    impl Ord for Record {
+        fn cmp(&self, other: &Self) -> Ordering {
+            self.key.cmp(&other.key)
+        }
+    }
+
+    impl RadixKey for Record {
+        const BYTES: usize = i32::BYTES;
+
+        fn key(&self) -> u64 {
+            self.key.key()
+        }
+    }
+
+    pub struct SortCheck {
+        // Check that equal records keep their relative order.
+        //
+        pub stable: bool,
+        // Number of random collections, besides the edge cases.
+        //
+        pub cases: usize,
+        // Max length of the random collections.
+        //
+        pub max_len: usize,
+        // Length of the single huge collection; quadratic sorts should lower it.
+        //
+        pub huge_len: usize,
+    }
+
+    impl Default for SortCheck {
+        fn default() -> Self {
+            Self {
+                stable: false,
+                cases: 200,
+                max_len: 64,
+                huge_len: 100_000,
+            }
+        }
+    }
+
+    impl SortCheck {
+        pub fn run<S: Fn(Vec<Record>) -> Vec<Record>>(&self, sort: S) {
+            let seed = std::env::var("SORT_CHECK_SEED")
+                .ok()
+                .and_then(|seed| seed.parse().ok())
+                .unwrap_or_else(|| fastrand::u64(..));
+
+            let rng = fastrand::Rng::with_seed(seed);
+
+            for keys in self.inputs(&rng) {
+                if self.check(&sort, &keys).is_err() {
+                    let input_len = keys.len();
+                    let keys = self.shrink(&sort, keys);
+                    let error = self.check(&sort, &keys).unwrap_err();
+
+                    panic!(
+                        "{}\ninput (shrunk from {} to {} records): {:?}\nseed: {} (rerun with SORT_CHECK_SEED={})",
+                        error, input_len, keys.len(), keys, seed, seed
+                    );
+                }
+            }
+        }
+
+        fn inputs(&self, rng: &fastrand::Rng) -> Vec<Vec<i32>> {
+            let random_keys = |len: usize, range: std::ops::RangeInclusive<i32>| {
+                (0..len).map(|_| rng.i32(range.clone())).collect::<Vec<_>>()
+            };
+
+            let mut inputs = vec![
+                vec![],
+                vec![rng.i32(KEY_RANGE)],
+                vec![rng.i32(KEY_RANGE); self.max_len],
+                (0..self.max_len as i32).collect(),
+                (0..self.max_len as i32).rev().collect(),
+                random_keys(self.huge_len, KEY_RANGE),
+            ];
+
+            for _ in 0..self.cases {
+                // Narrow ranges yield many duplicates.
+                //
+                let range_end = *[1, 8, *KEY_RANGE.end()].get(rng.usize(..3)).unwrap();
+
+                inputs.push(random_keys(
+                    rng.usize(..=self.max_len),
+                    -range_end..=range_end,
+                ));
+            }
+
+            inputs
+        }
+
+        fn check<S: Fn(Vec<Record>) -> Vec<Record>>(
+            &self,
+            sort: &S,
+            keys: &[i32],
+        ) -> Result<(), String> {
+            let input = keys
+                .iter()
+                .enumerate()
+                .map(|(id, key)| Record { key: *key, id })
+                .collect::<Vec<_>>();
+
+            let output = panic::catch_unwind(AssertUnwindSafe(|| sort(input.clone())))
+                .map_err(|_| "the sort panicked".to_string())?;
+
+            // Since the ids are unique, sorting by them restores the input, if the output is a
+            // permutation of it.
+            //
+            let mut restored_output = output.clone();
+            restored_output.sort_by_key(|record| record.id);
+
+            let is_permutation = restored_output.len() == input.len()
+                && restored_output
+                    .iter()
+                    .zip(input.iter())
+                    .all(|(a, b)| (a.key, a.id) == (b.key, b.id));
+
+            if !is_permutation {
+                return Err(format!(
+                    "the output is not a permutation of the input: {:?}",
+                    output
+                ));
+            }
+
+            if let Some(pair) = output.windows(2).find(|pair| pair[0].key > pair[1].key) {
+                return Err(format!("the output is not ordered: {:?}", pair));
+            }
+
+            if self.stable {
+                if let Some(pair) = output
+                    .windows(2)
+                    .find(|pair| pair[0].key == pair[1].key && pair[0].id > pair[1].id)
+                {
+                    return Err(format!("the output is not stable: {:?}", pair));
+                }
+            }
+
+            Ok(())
+        }
+
+        // Repeatedly removes chunks of decreasing size, and moves the keys towards zero, as long as
+        // the check keeps failing.
+        //
+        fn shrink<S: Fn(Vec<Record>) -> Vec<Record>>(
+            &self,
+            sort: &S,
+            mut keys: Vec<i32>,
+        ) -> Vec<i32> {
+            let mut attempts = 0;
+            let mut fails = |candidate: &[i32]| {
+                attempts += 1;
+                attempts <= MAX_SHRINK_ATTEMPTS && self.check(sort, candidate).is_err()
+            };
+
+            loop {
+                let mut shrunk = false;
+                let mut chunk_len = keys.len() / 2;
+
+                while chunk_len > 0 {
+                    let mut start = 0;
+
+                    while start + chunk_len <= keys.len() {
+                        let candidate = [&keys[..start], &keys[start + chunk_len..]].concat();
+
+                        if fails(&candidate) {
+                            keys = candidate;
+                            shrunk = true;
+                        } else {
+                            start += chunk_len;
+                        }
+                    }
+
+                    chunk_len /= 2;
+                }
+
+                for i in 0..keys.len() {
+                    if keys[i] != 0 {
+                        let mut candidate = keys.clone();
+                        candidate[i] /= 2;
+
+                        if fails(&candidate) {
+                            keys = candidate;
+                            shrunk = true;
+                        }
+                    }
+                }
+
+                if !shrunk {
+                    return keys;
+                }
+            }
+        }
+    }
+}
+
+// Checks for the float sorts: the inputs mix NaNs of both signs, ±0, infinities, subnormals and
+// duplicates, and the output must be an ordered permutation, according to the `FloatOrder`.
+//
+#[cfg(test)]
+pub mod float_order {
+    use crate::d2_5_sorter::FloatOrder;
+    use std::cmp::Ordering;
+
+    pub const ORDERS: [FloatOrder; 3] =
+        [FloatOrder::Total, FloatOrder::NanFirst, FloatOrder::NanLast];
+
+    pub fn special_floats() -> Vec<f64> {
+        vec![
+            1.5,
+            f64::NAN,
+            -0.0,
+            f64::INFINITY,
+            -1.5,
+            0.0,
+            -f64::NAN,
+            f64::NEG_INFINITY,
+            f64::MIN_POSITIVE / 2.0,
+            1.5,
+            f64::MAX,
+            -0.0,
+            f64::NAN,
+            f64::MIN,
+            0.0,
+            -f64::MIN_POSITIVE,
+        ]
+    }
+
+    // The special values, shuffled with random ones.
+    //
+    pub fn float_inputs() -> Vec<Vec<f64>> {
+        let mut inputs = vec![vec![], vec![f64::NAN], special_floats()];
+
+        for len in &[10, 100, 1000] {
+            let mut input = (0..*len)
+                .map(|_| {
+                    let specials = special_floats();
+                    if fastrand::u8(..4) == 0 {
+                        specials[fastrand::usize(..specials.len())]
+                    } else {
+                        (fastrand::f64() - 0.5) * 100.0
+                    }
+                })
+                .collect::<Vec<_>>();
+            fastrand::shuffle(&mut input);
+            inputs.push(input);
+        }
+
+        inputs
+    }
+
+    pub fn assert_float_order(input: &[f64], output: &[f64], order: FloatOrder) {
+        // The bits tell apart all the values, including ±0 and the NaNs.
+        //
+        let bits = |collection: &[f64]| {
+            let mut bits = collection
+                .iter()
+                .map(|value| value.to_bits())
+                .collect::<Vec<_>>();
+            bits.sort_unstable();
+            bits
+        };
+
+        assert_eq!(bits(input), bits(output), "not a permutation: {:?}", output);
+
+        if let Some(pair) = output
+            .windows(2)
+            .find(|pair| order.compare(&pair[0], &pair[1]) == Ordering::Greater)
+        {
+            panic!("not ordered ({:?}): {:?} in {:?}", order, pair, output);
+        }
+    }
+
+    // Runs the sort on all the inputs, with all the orders.
+    //
+    pub fn check_float_sort<S: Fn(&mut Vec<f64>, FloatOrder)>(sort: S) {
+        for input in float_inputs() {
+            for order in &ORDERS {
+                let mut output = input.clone();
+                sort(&mut output, *order);
+                assert_float_order(&input, &output, *order);
+            }
+        }
+    }
+}
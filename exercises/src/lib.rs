@@ -2,11 +2,22 @@ mod helpers;
 
 // Don't forget to add all the modules, so that the UTs are run.
 //
+pub mod d2_10_natural_merge_sort;
+pub mod d2_11_binary_heap;
+pub mod d2_12_random_quality;
+pub mod d2_13_selection;
+pub mod d2_14_sorted_data;
+pub mod d2_15_sampling;
 pub mod d2_1_bubble_sort;
 pub mod d2_2_merge_sort;
 pub mod d2_2_merge_sort_source;
 pub mod d2_3_quicksort;
 pub mod d2_4_dynamic_programming;
+pub mod d2_5_sorter;
+pub mod d2_6_instrumented_sorts;
+pub mod d2_7_introsort;
+pub mod d2_8_external_sort;
+pub mod d2_9_radix_sort;
 pub mod d3_1_linked_list;
 pub mod d3_2_doubly_linked_list;
 pub mod d3_3_binary_tree;
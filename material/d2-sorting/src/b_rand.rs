@@ -1,9 +1,12 @@
 use lazy_static::lazy_static;
 use num_bigint::BigUint;
-use num_traits::ToPrimitive;
-use std::ops::Rem;
+use num_traits::{One, ToPrimitive, Zero};
+use rand_core::{impls, RngCore, SeedableRng};
 use std::sync::Mutex;
 
+//Same generator as the exercises, which also implements RngCore, SeedableRng and jump().
+pub use hands_on_algos::d2_3_quicksort::RandGen;
+
 lazy_static! {
     static ref RG: Mutex<RandGen> = Mutex::new(RandGen::new(23, 21321, 21323, 314));
 }
@@ -12,32 +15,7 @@ pub fn rand(max: usize) -> usize {
     RG.lock().unwrap().next().unwrap() % max
 }
 
-pub struct RandGen {
-    curr: usize,
-    mul: usize,
-    inc: usize,
-    modulo: usize,
-}
-
-impl RandGen {
-    pub fn new(curr: usize, mul: usize, inc: usize, modulo: usize) -> Self {
-        RandGen {
-            curr,
-            mul,
-            inc,
-            modulo,
-        }
-    }
-}
-
-impl Iterator for RandGen {
-    type Item = usize;
-    fn next(&mut self) -> Option<Self::Item> {
-        self.curr = (self.curr * self.mul + self.inc) % self.modulo;
-        Some(self.curr)
-    }
-}
-
+#[derive(Clone, Debug)]
 pub struct BigGen {
     curr: BigUint,
     mul: usize,
@@ -48,22 +26,180 @@ pub struct BigGen {
 
 impl BigGen {
     pub fn new(curr: usize, max: usize) -> Self {
-        let mut mm: BigUint = usize::max_value().into();
-        mm = mm + (53 as usize);
+        let mut mm: BigUint = usize::MAX.into();
+        mm += 53_usize;
         BigGen {
             curr: curr.into(),
             mul: 4531345392834523213,
             inc: 3251235234162363461,
-            modulo: mm * usize::max_value(),
+            modulo: mm * usize::MAX,
             max,
         }
     }
+
+    fn step(&mut self) {
+        self.curr = (&self.curr * self.mul + self.inc) % &self.modulo;
+    }
+
+    //Advance by `steps` in O(log(steps)): n steps are x -> a*x + c, and the (a, c) for
+    //1, 2, 4.. steps are squared, and combined for the bits of steps.
+    pub fn jump(&mut self, mut steps: u64) {
+        let mut total_mul = BigUint::one();
+        let mut total_inc = BigUint::zero();
+        let mut mul = BigUint::from(self.mul);
+        let mut inc = BigUint::from(self.inc);
+        while steps > 0 {
+            if steps & 1 == 1 {
+                total_mul = total_mul * &mul % &self.modulo;
+                total_inc = (total_inc * &mul + &inc) % &self.modulo;
+            }
+            inc = (&mul + 1_usize) * inc % &self.modulo;
+            mul = &mul * &mul % &self.modulo;
+            steps >>= 1;
+        }
+        self.curr = (total_mul * &self.curr + total_inc) % &self.modulo;
+    }
+
+    //Hull-Dobell (see RandGen::has_full_period()).
+    pub fn has_full_period(&self) -> bool {
+        let mul_dec = (BigUint::from(self.mul) + &self.modulo - 1_usize) % &self.modulo;
+        let mut remaining = self.modulo.clone();
+        loop {
+            let common = gcd(remaining.clone(), mul_dec.clone());
+            if common.is_one() {
+                break;
+            }
+            remaining /= common;
+        }
+        let four = BigUint::from(4_usize);
+        gcd(self.inc.into(), self.modulo.clone()).is_one()
+            && remaining.is_one()
+            && (!(&self.modulo % &four).is_zero() || (&mul_dec % &four).is_zero())
+    }
+
+    //The modulo if full, otherwise measured with Brent's algorithm; None if over max_steps.
+    pub fn period(&self, max_steps: usize) -> Option<BigUint> {
+        if self.has_full_period() {
+            return Some(self.modulo.clone());
+        }
+        let mut tortoise = self.clone();
+        let mut hare = self.clone();
+        hare.step();
+        let (mut power, mut len) = (1_usize, 1_usize);
+        for _ in 0..max_steps {
+            if tortoise.curr == hare.curr {
+                return Some(len.into());
+            }
+            if power == len {
+                tortoise.curr = hare.curr.clone();
+                power *= 2;
+                len = 0;
+            }
+            hare.step();
+            len += 1;
+        }
+        None
+    }
+}
+
+fn gcd(mut a: BigUint, mut b: BigUint) -> BigUint {
+    while !b.is_zero() {
+        let r = a % &b;
+        a = b;
+        b = r;
+    }
+    a
 }
 
 impl Iterator for BigGen {
     type Item = usize;
     fn next(&mut self) -> Option<Self::Item> {
-        self.curr = (self.curr.clone() * self.mul + self.inc) % self.modulo.clone();
-        self.curr.clone().rem(self.max).to_usize()
+        self.step();
+        (&self.curr % self.max).to_usize()
+    }
+}
+
+//The modulo is ~2^128, so the middle 64 bits are used.
+impl RngCore for BigGen {
+    fn next_u32(&mut self) -> u32 {
+        self.next_u64() as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.step();
+        ((&self.curr >> 32_usize) % (BigUint::from(u64::MAX) + 1_usize))
+            .to_u64()
+            .unwrap()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        impls::fill_bytes_via_next(self, dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+//The iterator max is set to usize::MAX.
+impl SeedableRng for BigGen {
+    type Seed = [u8; 8];
+
+    fn from_seed(seed: Self::Seed) -> Self {
+        BigGen::new(usize::from_le_bytes(seed), usize::MAX)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_big_gen_jump() {
+        let b = BigGen::new(55, 1000);
+        let mut stepped = b.clone();
+        for steps in 0..100 {
+            let mut jumped = b.clone();
+            jumped.jump(steps);
+            assert_eq!(jumped.curr, stepped.curr);
+            stepped.next();
+        }
+    }
+
+    #[test]
+    fn test_big_gen_rng() {
+        let mut b = BigGen::seed_from_u64(3);
+        let mut c = b.clone();
+        let a: Vec<u64> = (0..8).map(|_| b.next_u64()).collect();
+        c.jump(4);
+        let c: Vec<u64> = (0..4).map(|_| c.next_u64()).collect();
+        assert_eq!(a[4..], c[..]);
+        assert_eq!(BigGen::seed_from_u64(3).next_u64(), a[0]);
+    }
+
+    #[test]
+    fn test_big_gen_shuffle() {
+        use hands_on_algos::d2_15_sampling::{random_subset, shuffle, AliasTable};
+
+        let mut v: Vec<u32> = (0..100).collect();
+        shuffle(&mut v, &mut BigGen::seed_from_u64(5));
+        let mut w: Vec<u32> = (0..100).collect();
+        shuffle(&mut w, &mut BigGen::seed_from_u64(5));
+        assert_eq!(v, w);
+        w.sort();
+        assert_eq!(w, (0..100).collect::<Vec<_>>());
+
+        let mut b = BigGen::seed_from_u64(6);
+        assert_eq!(random_subset(&v, 10, &mut b).len(), 10);
+        let table = AliasTable::new(&[0.0, 1.0]).unwrap();
+        assert!((0..100).all(|_| table.sample(&mut b) == 1));
+    }
+
+    #[test]
+    fn test_big_gen_period() {
+        let b = BigGen::new(55, 1000);
+        assert!(!b.has_full_period());
+        assert_eq!(b.period(1000), None);
     }
 }
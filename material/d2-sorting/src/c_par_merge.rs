@@ -0,0 +1,148 @@
+use hands_on_algos::d2_5_sorter::Sorter;
+use std::cmp::Ordering;
+
+//Below this many elements, the parallel overhead is higher than the gain.
+pub const DEFAULT_THRESHOLD: usize = 4096;
+
+//One scratch buffer is allocated; at each level, the halves are sorted (in parallel), merged
+//(in parallel) into the buffer, and copied back.
+pub fn par_merge_sort_by<T, F>(v: &mut [T], threshold: usize, cmp: F)
+where
+    T: Copy + Send + Sync,
+    F: Fn(&T, &T) -> Ordering + Sync,
+{
+    let mut buf = v.to_vec();
+    sort_rec(v, &mut buf, threshold, &cmp);
+}
+
+fn sort_rec<T, F>(v: &mut [T], buf: &mut [T], threshold: usize, cmp: &F)
+where
+    T: Copy + Send + Sync,
+    F: Fn(&T, &T) -> Ordering + Sync,
+{
+    if v.len() <= 1 {
+        return;
+    }
+    let mid = v.len() / 2;
+    let parallel = v.len() > threshold;
+    {
+        let (a, b) = v.split_at_mut(mid);
+        let (buf_a, buf_b) = buf.split_at_mut(mid);
+        if parallel {
+            rayon::join(
+                || sort_rec(a, buf_a, threshold, cmp),
+                || sort_rec(b, buf_b, threshold, cmp),
+            );
+        } else {
+            sort_rec(a, buf_a, threshold, cmp);
+            sort_rec(b, buf_b, threshold, cmp);
+        }
+    }
+    let (a, b) = v.split_at(mid);
+    par_merge(a, b, buf, threshold, cmp);
+    v.copy_from_slice(buf);
+}
+
+//Splits the longer of the two halves at its middle, and finds with a binary search where its
+//middle element goes in the other one; the two pairs of parts can then be merged independently.
+//On equal elements, the ones in `a` go first, so the sort is stable.
+//Two elements can't always be split (a=[x], b=[y] with x <= y gives ma = mb = 0, so the right pair
+//is the input again), so up to two are merged sequentially, whatever the threshold.
+fn par_merge<T, F>(a: &[T], b: &[T], out: &mut [T], threshold: usize, cmp: &F)
+where
+    T: Copy + Send + Sync,
+    F: Fn(&T, &T) -> Ordering + Sync,
+{
+    if a.len() + b.len() <= threshold.max(2) || a.is_empty() || b.is_empty() {
+        seq_merge(a, b, out, cmp);
+        return;
+    }
+    let (ma, mb) = if a.len() >= b.len() {
+        let ma = a.len() / 2;
+        //b elements lower than a[ma] go to the left
+        let mb = b.partition_point(|x| cmp(x, &a[ma]) == Ordering::Less);
+        (ma, mb)
+    } else {
+        let mb = b.len() / 2;
+        //a elements lower or equal to b[mb] go to the left
+        let ma = a.partition_point(|x| cmp(x, &b[mb]) != Ordering::Greater);
+        (ma, mb)
+    };
+    let (out_l, out_r) = out.split_at_mut(ma + mb);
+    rayon::join(
+        || par_merge(&a[..ma], &b[..mb], out_l, threshold, cmp),
+        || par_merge(&a[ma..], &b[mb..], out_r, threshold, cmp),
+    );
+}
+
+fn seq_merge<T: Copy, F: Fn(&T, &T) -> Ordering>(a: &[T], b: &[T], out: &mut [T], cmp: &F) {
+    let (mut i, mut j) = (0, 0);
+    for o in out.iter_mut() {
+        if j == b.len() || (i < a.len() && cmp(&b[j], &a[i]) != Ordering::Less) {
+            *o = a[i];
+            i += 1;
+        } else {
+            *o = b[j];
+            j += 1;
+        }
+    }
+}
+
+pub struct ParMergeSort {
+    pub threshold: usize,
+}
+
+impl<T: Copy + Send + Sync> Sorter<T> for ParMergeSort {
+    fn sort_by<F: Fn(&T, &T) -> Ordering + Sync>(&self, v: &mut [T], cmp: F) {
+        par_merge_sort_by(v, self.threshold, cmp)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_par_merge_sort() {
+        let mut v: Vec<u32> = (0..100_000).map(|_| rand::random::<u32>() % 1000).collect();
+        let mut expected = v.clone();
+        expected.sort();
+
+        //Small threshold, so that the parallel paths are exercised.
+        ParMergeSort { threshold: 64 }.sort(&mut v);
+        assert_eq!(v, expected);
+
+        let mut empty: Vec<u32> = vec![];
+        ParMergeSort {
+            threshold: DEFAULT_THRESHOLD,
+        }
+        .sort(&mut empty);
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn test_par_merge_sort_stable() {
+        let mut v: Vec<(u8, usize)> = (0..50_000).map(|i| (rand::random::<u8>() % 8, i)).collect();
+        let mut expected = v.clone();
+        expected.sort_by_key(|x| x.0);
+
+        ParMergeSort { threshold: 16 }.sort_by_key(&mut v, |x| x.0);
+        assert_eq!(v, expected);
+    }
+
+    #[test]
+    fn test_par_merge_sort_tiny_threshold() {
+        for threshold in 0..=2 {
+            let mut v = vec![1, 2];
+            ParMergeSort { threshold }.sort(&mut v);
+            assert_eq!(v, vec![1, 2]);
+
+            let mut v: Vec<u32> = (0..1000).map(|_| rand::random::<u32>() % 100).collect();
+            let mut expected = v.clone();
+            expected.sort();
+
+            ParMergeSort { threshold }.sort(&mut v);
+            assert_eq!(v, expected);
+        }
+    }
+}
@@ -0,0 +1,361 @@
+use hands_on_algos::d2_10_natural_merge_sort::natural_merge_sort;
+use hands_on_algos::d2_11_binary_heap::heap_sort;
+use hands_on_algos::d2_1_bubble_sort::bubble_sort;
+use hands_on_algos::d2_2_merge_sort::{merge_sort_improved, merge_sort_stable};
+use hands_on_algos::d2_2_merge_sort_source::source_merge_sort;
+use hands_on_algos::d2_3_quicksort::{
+    quicksort, quicksort_3way, quicksort_with, threaded_quicksort, MedianOfThreePivot, NintherPivot,
+};
+use hands_on_algos::d2_5_sorter::Sorter;
+use hands_on_algos::d2_7_introsort::introsort;
+use hands_on_algos::d2_8_external_sort::external_sort;
+use hands_on_algos::d2_9_radix_sort::{counting_sort, radix_sort_lsd, radix_sort_msd};
+use std::time::{Duration, Instant};
+use v1_sort::b_rand::{BigGen, RandGen};
+use v1_sort::c_par_merge::{ParMergeSort, DEFAULT_THRESHOLD};
+
+//Times every sort on several input shapes and sizes, checks the results against the std sort,
+//and prints the timings as csv or markdown tables.
+//
+//Run in release mode: cargo run --release --bin sort_bench -- [--csv|--markdown] [SIZE...]
+
+const DEFAULT_SIZES: [usize; 3] = [1_000, 10_000, 100_000];
+
+//Above this size, the O(n^2) sorts are skipped.
+const QUADRATIC_MAX_LEN: usize = 20_000;
+
+const EXTERNAL_SORT_CHUNK_SIZE: usize = 4096;
+
+//Quicksort on few unique values recurses once per duplicate, so the default stack is not enough.
+const STACK_SIZE: usize = 1 << 30;
+
+struct Shape {
+    name: &'static str,
+    gen: fn(usize) -> Vec<usize>,
+}
+
+struct Algorithm {
+    name: &'static str,
+    quadratic: bool,
+    sort: fn(&mut Vec<usize>),
+}
+
+struct Row {
+    shape: &'static str,
+    size: usize,
+    algorithm: &'static str,
+    //None if skipped
+    time: Option<Duration>,
+    correct: bool,
+}
+
+enum Format {
+    Csv,
+    Markdown,
+}
+
+fn shapes() -> Vec<Shape> {
+    vec![
+        Shape {
+            name: "random_biggen",
+            gen: |n| BigGen::new(55, n).take(n).collect(),
+        },
+        Shape {
+            name: "random_randgen",
+            gen: |n| {
+                RandGen::new(23, 1_103_515_245, 12_345, 1 << 31)
+                    .take(n)
+                    .map(|x| x % n)
+                    .collect()
+            },
+        },
+        Shape {
+            name: "sorted",
+            gen: |n| (0..n).collect(),
+        },
+        Shape {
+            name: "reversed",
+            gen: |n| (0..n).rev().collect(),
+        },
+        Shape {
+            name: "organ_pipe",
+            gen: |n| (0..n).map(|i| i.min(n - 1 - i)).collect(),
+        },
+        Shape {
+            name: "few_unique",
+            gen: |n| BigGen::new(55, 8).take(n).collect(),
+        },
+        Shape {
+            name: "nearly_sorted",
+            gen: |n| {
+                //1% of the elements swapped
+                let mut v: Vec<usize> = (0..n).collect();
+                let mut positions = BigGen::new(55, n);
+                for _ in 0..(n / 100).max(1) {
+                    let a = positions.next().unwrap();
+                    let b = positions.next().unwrap();
+                    v.swap(a, b);
+                }
+                v
+            },
+        },
+    ]
+}
+
+fn algorithms() -> Vec<Algorithm> {
+    vec![
+        Algorithm {
+            name: "d2_1 bubble_sort",
+            quadratic: true,
+            sort: |v| bubble_sort(v),
+        },
+        Algorithm {
+            name: "d2_2 merge_sort_improved",
+            quadratic: false,
+            sort: |v| *v = merge_sort_improved(v),
+        },
+        Algorithm {
+            name: "d2_2 merge_sort_stable",
+            quadratic: false,
+            sort: |v| merge_sort_stable(v),
+        },
+        Algorithm {
+            name: "d2_2 source_merge_sort",
+            quadratic: false,
+            sort: |v| *v = source_merge_sort(std::mem::take(v)),
+        },
+        Algorithm {
+            name: "d2_3 quicksort",
+            quadratic: false,
+            sort: |v| quicksort(v),
+        },
+        Algorithm {
+            name: "d2_3 quicksort (median of 3)",
+            quadratic: false,
+            sort: |v| quicksort_with(v, |a, b| a.cmp(b), &mut MedianOfThreePivot),
+        },
+        Algorithm {
+            name: "d2_3 quicksort (ninther)",
+            quadratic: false,
+            sort: |v| quicksort_with(v, |a, b| a.cmp(b), &mut NintherPivot),
+        },
+        Algorithm {
+            name: "d2_3 quicksort_3way",
+            quadratic: false,
+            sort: |v| quicksort_3way(v),
+        },
+        Algorithm {
+            name: "d2_3 threaded_quicksort",
+            quadratic: false,
+            sort: |v| threaded_quicksort(v),
+        },
+        Algorithm {
+            name: "d2_7 introsort",
+            quadratic: false,
+            sort: |v| introsort(v),
+        },
+        Algorithm {
+            name: "d2_8 external_sort",
+            quadratic: false,
+            sort: |v| {
+                *v = external_sort(std::mem::take(v), EXTERNAL_SORT_CHUNK_SIZE)
+                    .and_then(|sorted| sorted.collect())
+                    .expect("external sort failed")
+            },
+        },
+        Algorithm {
+            name: "d2_9 radix_sort_lsd",
+            quadratic: false,
+            sort: |v| radix_sort_lsd(v),
+        },
+        Algorithm {
+            //includes the conversion to/from big endian bytes
+            name: "d2_9 radix_sort_msd",
+            quadratic: false,
+            sort: |v| {
+                let mut bytes: Vec<[u8; 8]> = v.iter().map(|x| (*x as u64).to_be_bytes()).collect();
+                radix_sort_msd(&mut bytes);
+                *v = bytes
+                    .into_iter()
+                    .map(|b| u64::from_be_bytes(b) as usize)
+                    .collect();
+            },
+        },
+        Algorithm {
+            name: "d2_9 counting_sort",
+            quadratic: false,
+            sort: |v| counting_sort(v),
+        },
+        Algorithm {
+            name: "d2_10 natural_merge_sort",
+            quadratic: false,
+            sort: |v| {
+                natural_merge_sort(v);
+            },
+        },
+        Algorithm {
+            name: "d2_11 heap_sort",
+            quadratic: false,
+            sort: |v| heap_sort(v),
+        },
+        Algorithm {
+            name: "v1 insert_sort",
+            quadratic: true,
+            sort: |v| v1_sort::insert_sort(v),
+        },
+        Algorithm {
+            name: "v1 bubble_sort",
+            quadratic: true,
+            sort: |v| v1_sort::bubble_sort(v),
+        },
+        Algorithm {
+            name: "v1 merge_sort",
+            quadratic: false,
+            sort: |v| *v = v1_sort::merge_sort(std::mem::take(v)),
+        },
+        Algorithm {
+            name: "v1 quick_sort",
+            quadratic: false,
+            sort: |v| v1_sort::quick_sort(v),
+        },
+        Algorithm {
+            name: "v1 quick_sort (ninther)",
+            quadratic: false,
+            sort: |v| v1_sort::quick_sort_with(v, |a, b| a.cmp(b), &mut NintherPivot),
+        },
+        Algorithm {
+            name: "v1 par_merge_sort",
+            quadratic: false,
+            sort: |v| {
+                ParMergeSort {
+                    threshold: DEFAULT_THRESHOLD,
+                }
+                .sort(v)
+            },
+        },
+    ]
+}
+
+fn run(sizes: &[usize]) -> Vec<Row> {
+    let mut rows = Vec::new();
+    for &size in sizes {
+        for shape in shapes() {
+            let input = (shape.gen)(size);
+            let mut expected = input.clone();
+            expected.sort_unstable();
+
+            for algorithm in algorithms() {
+                let mut row = Row {
+                    shape: shape.name,
+                    size,
+                    algorithm: algorithm.name,
+                    time: None,
+                    correct: false,
+                };
+                if !algorithm.quadratic || size <= QUADRATIC_MAX_LEN {
+                    let mut v = input.clone();
+                    let start = Instant::now();
+                    (algorithm.sort)(&mut v);
+                    row.time = Some(start.elapsed());
+                    row.correct = v == expected;
+                }
+                rows.push(row);
+            }
+        }
+    }
+    rows
+}
+
+fn millis(time: Duration) -> String {
+    format!("{:.3}", time.as_secs_f64() * 1000.0)
+}
+
+fn print_csv(rows: &[Row]) {
+    println!("shape,size,algorithm,time_ms,correct");
+    for row in rows {
+        if let Some(time) = row.time {
+            println!(
+                "{},{},{},{},{}",
+                row.shape,
+                row.size,
+                row.algorithm,
+                millis(time),
+                row.correct
+            );
+        }
+    }
+}
+
+//One table per size, with the algorithms as rows and the shapes as columns.
+fn print_markdown(rows: &[Row], sizes: &[usize]) {
+    let shapes = shapes();
+    for &size in sizes {
+        println!("### {} elements (ms)\n", size);
+        print!("| algorithm |");
+        for shape in &shapes {
+            print!(" {} |", shape.name);
+        }
+        print!("\n|---|");
+        for _ in &shapes {
+            print!("---:|");
+        }
+        println!();
+
+        for algorithm in algorithms() {
+            print!("| {} |", algorithm.name);
+            for shape in &shapes {
+                let row = rows
+                    .iter()
+                    .find(|r| {
+                        r.size == size && r.shape == shape.name && r.algorithm == algorithm.name
+                    })
+                    .unwrap();
+                match (row.time, row.correct) {
+                    (None, _) => print!(" - |"),
+                    (Some(time), true) => print!(" {} |", millis(time)),
+                    (Some(time), false) => print!(" {} WRONG |", millis(time)),
+                }
+            }
+            println!();
+        }
+        println!();
+    }
+}
+
+fn usage() -> ! {
+    eprintln!("usage: sort_bench [--csv|--markdown] [SIZE...]");
+    std::process::exit(1);
+}
+
+fn main() {
+    let mut format = Format::Markdown;
+    let mut sizes = Vec::new();
+    for arg in std::env::args().skip(1) {
+        match arg.as_str() {
+            "--csv" => format = Format::Csv,
+            "--markdown" => format = Format::Markdown,
+            _ => match arg.parse::<usize>() {
+                Ok(size) if size > 0 => sizes.push(size),
+                _ => usage(),
+            },
+        }
+    }
+    if sizes.is_empty() {
+        sizes = DEFAULT_SIZES.to_vec();
+    }
+
+    let rows = {
+        let sizes = sizes.clone();
+        std::thread::Builder::new()
+            .stack_size(STACK_SIZE)
+            .spawn(move || run(&sizes))
+            .unwrap()
+            .join()
+            .unwrap()
+    };
+
+    match format {
+        Format::Csv => print_csv(&rows),
+        Format::Markdown => print_markdown(&rows, &sizes),
+    }
+}
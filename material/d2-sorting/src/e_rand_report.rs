@@ -0,0 +1,115 @@
+use hands_on_algos::d2_12_random_quality::{run_battery, uniform_from_iter, uniform_from_rng};
+use rand::rngs::{mock::StepRng, StdRng};
+use rand::SeedableRng;
+use v1_sort::b_rand::{BigGen, RandGen};
+
+//Runs the statistical tests on our generators and rand's, and prints a pass/fail table, followed
+//by the periods of our small generators.
+//
+//cargo run --release --bin rand_report -- [SAMPLE_SIZE]
+
+const DEFAULT_SAMPLE_SIZE: usize = 100_000;
+
+//Non-full periods are measured by stepping the generator, up to this many steps.
+const PERIOD_MAX_STEPS: usize = 1_000_000;
+
+fn main() {
+    let sample_size = match std::env::args().nth(1) {
+        Some(arg) => arg.parse().unwrap_or_else(|_| {
+            eprintln!("usage: rand_report [SAMPLE_SIZE]");
+            std::process::exit(1);
+        }),
+        None => DEFAULT_SAMPLE_SIZE,
+    };
+
+    let generators: Vec<(&str, Box<dyn Iterator<Item = f64>>)> = vec![
+        (
+            "RandGen(23, 21321, 21323, 314)",
+            Box::new(uniform_from_iter(RandGen::new(23, 21321, 21323, 314), 314)),
+        ),
+        (
+            "RandGen(1, 1103515245, 12345, 2^31)",
+            Box::new(uniform_from_iter(
+                RandGen::new(1, 1_103_515_245, 12_345, 1 << 31),
+                1 << 31,
+            )),
+        ),
+        (
+            "RandGen::seed_from_u64 (MMIX, 2^63)",
+            Box::new(uniform_from_rng(RandGen::seed_from_u64(55))),
+        ),
+        (
+            "BigGen(55, 100)",
+            Box::new(uniform_from_iter(BigGen::new(55, 100), 100)),
+        ),
+        (
+            "BigGen::seed_from_u64",
+            Box::new(uniform_from_rng(BigGen::seed_from_u64(55))),
+        ),
+        (
+            "rand StdRng",
+            Box::new(uniform_from_rng(StdRng::seed_from_u64(55))),
+        ),
+        (
+            "rand thread_rng",
+            Box::new(uniform_from_rng(rand::thread_rng())),
+        ),
+        (
+            "rand StepRng (counter)",
+            Box::new(uniform_from_rng(StepRng::new(0, 0x9E37_79B9_7F4A_7C15))),
+        ),
+    ];
+
+    println!("{} samples per generator, fail if p < 0.001\n", sample_size);
+
+    let mut header_printed = false;
+    for (name, generator) in generators {
+        let sample: Vec<f64> = generator.take(sample_size).collect();
+        let results = run_battery(&sample);
+
+        if !header_printed {
+            print!("| generator |");
+            for result in &results {
+                print!(" {} |", result.name);
+            }
+            print!("\n|---|");
+            for _ in &results {
+                print!("---|");
+            }
+            println!();
+            header_printed = true;
+        }
+
+        print!("| {} |", name);
+        for result in &results {
+            let verdict = if result.passed() { "pass" } else { "FAIL" };
+            print!(" {} (p={:.4}) |", verdict, result.p_value);
+        }
+        println!();
+    }
+
+    let over_max = || format!("> {}", PERIOD_MAX_STEPS);
+    let rand_gen = RandGen::new(23, 21321, 21323, 314);
+    let big_gen = BigGen::new(55, 100);
+    let periods = vec![
+        (
+            "RandGen(23, 21321, 21323, 314)",
+            rand_gen.has_full_period(),
+            rand_gen
+                .period(PERIOD_MAX_STEPS)
+                .map_or_else(over_max, |period| period.to_string()),
+        ),
+        (
+            "BigGen(55, 100)",
+            big_gen.has_full_period(),
+            big_gen
+                .period(PERIOD_MAX_STEPS)
+                .map_or_else(over_max, |period| period.to_string()),
+        ),
+    ];
+
+    println!("\n| generator | full period | period |\n|---|---|---|");
+    for (name, full_period, period) in periods {
+        println!("| {} | {} | {} |", name, full_period, period);
+    }
+}
@@ -0,0 +1,394 @@
+use hands_on_algos::d2_6_instrumented_sorts::{
+    bubble_sort_instrumented, merge_sort_improved_instrumented, quicksort_instrumented, Instrument,
+    SortStep,
+};
+use rand::seq::SliceRandom;
+use std::io::Write;
+use std::sync::mpsc::RecvTimeoutError;
+use std::time::{Duration, Instant};
+use termion::color;
+use termion::event::Key;
+use termion::input::TermRead;
+use termion::raw::IntoRawMode;
+use termion::screen::AlternateScreen;
+
+//Animates the sorts as bar charts: the sort is traced first (see d2_6), then the trace is replayed
+//on the collection, one step at a time.
+//
+//cargo run --bin sort_viz
+//
+//keys: space pause, n/right step, +/- speed, tab/1-4 switch algorithm, r shuffle, q quit
+
+const FRAME: Duration = Duration::from_millis(30);
+
+//Steps per second.
+const DEFAULT_SPEED: u32 = 64;
+const MAX_SPEED: u32 = 8192;
+
+//Bars, at most; fewer on narrow terminals.
+const MAX_BARS: usize = 80;
+
+//Rows used by the status lines and the partition markers.
+const RESERVED_ROWS: u16 = 4;
+
+struct Algorithm {
+    name: &'static str,
+    trace: fn(&[u16]) -> Vec<SortStep<u16>>,
+}
+
+fn algorithms() -> Vec<Algorithm> {
+    vec![
+        Algorithm {
+            name: "bubble_sort",
+            trace: |v| {
+                let mut instrument = Instrument::new(true);
+                bubble_sort_instrumented(&mut v.to_vec(), &mut instrument);
+                instrument.trace.unwrap()
+            },
+        },
+        Algorithm {
+            name: "insert_sort",
+            trace: insert_sort_trace,
+        },
+        Algorithm {
+            name: "quicksort",
+            trace: |v| {
+                let mut instrument = Instrument::new(true);
+                quicksort_instrumented(&mut v.to_vec(), &mut instrument);
+                instrument.trace.unwrap()
+            },
+        },
+        Algorithm {
+            name: "merge_sort_improved",
+            trace: |v| {
+                let mut instrument = Instrument::new(true);
+                merge_sort_improved_instrumented(v, &mut instrument);
+                instrument.trace.unwrap()
+            },
+        },
+    ]
+}
+
+//Mirrors v1_sort::insert_sort (there is no instrumented version); each pass is shown as the range
+//still to sort.
+fn insert_sort_trace(v: &[u16]) -> Vec<SortStep<u16>> {
+    let mut v = v.to_vec();
+    let mut trace = Vec::new();
+    for start in 0..v.len() {
+        trace.push(SortStep::Enter(start, v.len()));
+        let mut best = start;
+        for i in start..v.len() {
+            trace.push(SortStep::Compare(i, best));
+            if v[i] < v[best] {
+                best = i;
+            }
+        }
+        trace.push(SortStep::Swap(start, best));
+        v.swap(start, best);
+    }
+    trace
+}
+
+//The collection being sorted, and what the last steps touched.
+struct Replay {
+    values: Vec<u16>,
+    trace: Vec<SortStep<u16>>,
+    next: usize,
+    compared: Option<(usize, usize)>,
+    changed: Vec<usize>,
+    range: Option<(usize, usize)>,
+    pivot: Option<usize>,
+    //Only the quicksort records pivots.
+    partitions: bool,
+    //Pivots already in their final position.
+    placed: Vec<bool>,
+    comparisons: usize,
+    writes: usize,
+}
+
+impl Replay {
+    fn new(values: Vec<u16>, algorithm: &Algorithm) -> Self {
+        let trace = (algorithm.trace)(&values);
+        Replay {
+            partitions: trace.iter().any(|step| matches!(step, SortStep::Pivot(_))),
+            trace,
+            placed: vec![false; values.len()],
+            values,
+            next: 0,
+            compared: None,
+            changed: Vec::new(),
+            range: None,
+            pivot: None,
+            comparisons: 0,
+            writes: 0,
+        }
+    }
+
+    fn done(&self) -> bool {
+        self.next == self.trace.len()
+    }
+
+    fn step(&mut self) {
+        let step = match self.trace.get(self.next) {
+            Some(step) => step.clone(),
+            None => return,
+        };
+        self.next += 1;
+        self.changed.clear();
+        match step {
+            SortStep::Compare(a, b) => {
+                self.comparisons += 1;
+                self.compared = Some((a, b));
+                //the partition compares everything with the pivot, which is the second index
+                if self.partitions {
+                    self.pivot = Some(b);
+                }
+            }
+            SortStep::Swap(a, b) => {
+                self.writes += 1;
+                self.values.swap(a, b);
+                self.changed.extend(&[a, b]);
+            }
+            SortStep::Write(i, value) => {
+                self.writes += 1;
+                self.values[i] = value;
+                self.changed.push(i);
+            }
+            SortStep::Enter(start, end) => {
+                self.range = Some((start, end));
+                self.pivot = None;
+            }
+            SortStep::Pivot(p) => {
+                self.placed[p] = true;
+                self.pivot = None;
+            }
+        }
+    }
+}
+
+struct Viz {
+    algorithms: Vec<Algorithm>,
+    current: usize,
+    original: Vec<u16>,
+    replay: Replay,
+    paused: bool,
+    speed: u32,
+    //Fractional steps carried over between frames.
+    pending: f64,
+}
+
+impl Viz {
+    fn new(len: usize) -> Self {
+        let algorithms = algorithms();
+        let original = shuffled(len);
+        let replay = Replay::new(original.clone(), &algorithms[0]);
+        Viz {
+            algorithms,
+            current: 0,
+            original,
+            replay,
+            paused: false,
+            speed: DEFAULT_SPEED,
+            pending: 0.0,
+        }
+    }
+
+    //The same values are sorted by each algorithm, so that they can be compared.
+    fn switch(&mut self, algorithm: usize) {
+        self.current = algorithm % self.algorithms.len();
+        self.replay = Replay::new(self.original.clone(), &self.algorithms[self.current]);
+        self.pending = 0.0;
+    }
+
+    fn shuffle(&mut self) {
+        self.original = shuffled(self.original.len());
+        self.switch(self.current);
+    }
+
+    //Returns false to quit.
+    fn key(&mut self, key: Key) -> bool {
+        match key {
+            Key::Char('q') | Key::Esc | Key::Ctrl('c') => return false,
+            Key::Char(' ') => self.paused = !self.paused,
+            Key::Char('n') | Key::Right => {
+                self.paused = true;
+                self.replay.step();
+            }
+            Key::Char('+') | Key::Char('=') => self.speed = (self.speed * 2).min(MAX_SPEED),
+            Key::Char('-') => self.speed = (self.speed / 2).max(1),
+            Key::Char('\t') => self.switch(self.current + 1),
+            Key::BackTab => self.switch(self.current + self.algorithms.len() - 1),
+            Key::Char(c @ '1'..='9') => {
+                let index = c as usize - '1' as usize;
+                if index < self.algorithms.len() {
+                    self.switch(index);
+                }
+            }
+            Key::Char('r') => self.shuffle(),
+            _ => {}
+        }
+        true
+    }
+
+    fn advance(&mut self, elapsed: Duration) {
+        if self.paused || self.replay.done() {
+            return;
+        }
+        self.pending += elapsed.as_secs_f64() * self.speed as f64;
+        while self.pending >= 1.0 && !self.replay.done() {
+            self.replay.step();
+            self.pending -= 1.0;
+        }
+    }
+
+    fn render(&self, width: u16, height: u16) -> String {
+        let replay = &self.replay;
+        let chart_height = height.saturating_sub(RESERVED_ROWS).max(1);
+        let max_value = self.original.len().max(1) as f64;
+        let bar_width = (width as usize / replay.values.len().max(1)).max(1);
+
+        let mut out = format!("{}", termion::clear::All);
+        for (i, value) in replay.values.iter().enumerate() {
+            let bar_height = ((*value as f64 / max_value) * chart_height as f64).ceil() as u16;
+            let bar = format!(
+                "{}{}{}",
+                self.color(i),
+                "█".repeat(bar_width.saturating_sub(1).max(1)),
+                color::Fg(color::Reset)
+            );
+            let x = (i * bar_width) as u16 + 1;
+            for row in 0..bar_height {
+                out += &format!("{}{}", termion::cursor::Goto(x, chart_height - row), bar);
+            }
+            //partition boundaries
+            if let Some((start, end)) = replay.range {
+                let marker = if i == start {
+                    "["
+                } else if i + 1 == end {
+                    "]"
+                } else if i > start && i < end {
+                    "-"
+                } else {
+                    " "
+                };
+                out += &format!("{}{}", termion::cursor::Goto(x, chart_height + 1), marker);
+            }
+        }
+
+        let state = if replay.done() {
+            "done"
+        } else if self.paused {
+            "paused"
+        } else {
+            "running"
+        };
+        out += &format!(
+            "{}{} [{}/{}] | {} | step {}/{} | comparisons {} | writes {} | {} steps/s",
+            termion::cursor::Goto(1, chart_height + 2),
+            self.algorithms[self.current].name,
+            self.current + 1,
+            self.algorithms.len(),
+            state,
+            replay.next,
+            replay.trace.len(),
+            replay.comparisons,
+            replay.writes,
+            self.speed
+        );
+        out += &format!(
+            "{}space pause | n/right step | +/- speed | tab/1-{} algorithm | r shuffle | q quit",
+            termion::cursor::Goto(1, chart_height + 3),
+            self.algorithms.len()
+        );
+        out
+    }
+
+    fn color(&self, i: usize) -> String {
+        let replay = &self.replay;
+        if replay.done() {
+            format!("{}", color::Fg(color::Green))
+        } else if replay.pivot == Some(i) {
+            format!("{}", color::Fg(color::Magenta))
+        } else if replay.changed.contains(&i) {
+            format!("{}", color::Fg(color::Red))
+        } else if replay.compared.is_some_and(|(a, b)| a == i || b == i) {
+            format!("{}", color::Fg(color::Yellow))
+        } else if replay.placed[i] {
+            format!("{}", color::Fg(color::Green))
+        } else if replay
+            .range
+            .is_some_and(|(start, end)| i >= start && i < end)
+        {
+            format!("{}", color::Fg(color::White))
+        } else {
+            format!("{}", color::Fg(color::LightBlack))
+        }
+    }
+}
+
+//A permutation of 1..=len, so that the bars have distinct heights.
+fn shuffled(len: usize) -> Vec<u16> {
+    let mut values: Vec<u16> = (1..=len as u16).collect();
+    values.shuffle(&mut rand::thread_rng());
+    values
+}
+
+fn main() {
+    let (width, height) = termion::terminal_size().unwrap_or((80, 24));
+    let len = (width as usize / 2).clamp(2, MAX_BARS);
+
+    //keys are read on a separate thread, so that the animation isn't blocked
+    let (ch_s, ch_r) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        let stdin = std::io::stdin();
+        for k in stdin.keys() {
+            if ch_s.send(k).is_err() {
+                return;
+            }
+        }
+    });
+
+    let mut screen = AlternateScreen::from(std::io::stdout().into_raw_mode().unwrap());
+    write!(screen, "{}", termion::cursor::Hide).unwrap();
+
+    let mut viz = Viz::new(len);
+    let mut last_frame = Instant::now();
+    loop {
+        let (width, height) = termion::terminal_size().unwrap_or((width, height));
+        write!(screen, "{}", viz.render(width, height)).unwrap();
+        screen.flush().unwrap();
+
+        match ch_r.recv_timeout(FRAME) {
+            Ok(Ok(key)) => {
+                if !viz.key(key) {
+                    break;
+                }
+            }
+            Ok(Err(_)) | Err(RecvTimeoutError::Disconnected) => break,
+            Err(RecvTimeoutError::Timeout) => {}
+        }
+
+        let now = Instant::now();
+        viz.advance(now - last_frame);
+        last_frame = now;
+    }
+
+    write!(screen, "{}", termion::cursor::Show).unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_traces_sort() {
+        for algorithm in algorithms() {
+            let mut replay = Replay::new(shuffled(50), &algorithm);
+            while !replay.done() {
+                replay.step();
+            }
+            let expected: Vec<u16> = (1..=50).collect();
+            assert_eq!(replay.values, expected, "{}", algorithm.name);
+        }
+    }
+}
@@ -0,0 +1,280 @@
+pub mod b_rand;
+pub mod c_par_merge;
+
+use hands_on_algos::d2_3_quicksort::PivotStrategy;
+use hands_on_algos::d2_5_sorter::{partial_compare, Sorter};
+use std::cmp::Ordering;
+
+pub fn insert_sort<T: PartialOrd>(v: &mut [T]) {
+    insert_sort_by(v, partial_compare)
+}
+
+pub fn insert_sort_by<T, F: Fn(&T, &T) -> Ordering>(v: &mut [T], cmp: F) {
+    //fewest swaps
+    for start in 0..v.len() {
+        let mut best = start;
+        for i in start..v.len() {
+            if cmp(&v[i], &v[best]) == Ordering::Less {
+                best = i;
+            }
+        }
+        v.swap(start, best);
+    }
+}
+
+pub fn bubble_sort<T: PartialOrd>(v: &mut [T]) {
+    //1 pass if already sorted
+    for start in 0..v.len() {
+        let mut sorted = true; //add later
+        for i in start..(v.len() - 1) {
+            if v[i] > v[i + 1] {
+                v.swap(i, i + 1);
+                sorted = false;
+            }
+        }
+        if sorted {
+            return;
+        }
+    }
+}
+
+pub fn merge_sort<T: PartialOrd>(v: Vec<T>) -> Vec<T> {
+    merge_sort_by(v, partial_compare)
+}
+
+pub fn merge_sort_by<T, F: Fn(&T, &T) -> Ordering>(v: Vec<T>, cmp: F) -> Vec<T> {
+    merge_sort_rec(v, &cmp)
+}
+
+fn merge_sort_rec<T, F: Fn(&T, &T) -> Ordering>(mut v: Vec<T>, cmp: &F) -> Vec<T> {
+    if v.len() <= 1 {
+        return v;
+    }
+    let b = v.split_off(v.len() / 2);
+    let a = merge_sort_rec(v, cmp);
+    let b = merge_sort_rec(b, cmp);
+    let mut res = Vec::new();
+    let mut b_it = b.into_iter();
+    let mut a_it = a.into_iter();
+    let mut a_peak = a_it.next();
+    let mut b_peak = b_it.next();
+    loop {
+        match a_peak {
+            Some(ref a_val) => match b_peak {
+                Some(ref b_val) => {
+                    if cmp(b_val, a_val) == Ordering::Less {
+                        res.push(b_peak.take().unwrap());
+                        b_peak = b_it.next();
+                    } else {
+                        res.push(a_peak.take().unwrap());
+                        a_peak = a_it.next();
+                    }
+                }
+                None => {
+                    res.push(a_peak.take().unwrap());
+                    res.extend(a_it);
+                    return res;
+                }
+            },
+            None => {
+                if let Some(b_val) = b_peak {
+                    res.push(b_val)
+                }
+                res.extend(b_it);
+                return res;
+            }
+        }
+    }
+}
+
+//Picks a random pivot from the shared b_rand generator.
+pub struct SharedRandPivot;
+
+impl PivotStrategy for SharedRandPivot {
+    fn choose<T, F: Fn(&T, &T) -> Ordering>(&mut self, v: &[T], _cmp: &F) -> usize {
+        //rand::random::<usize>() % v.len()
+        b_rand::rand(v.len())
+    }
+}
+
+fn pivot<T, F: Fn(&T, &T) -> Ordering, S: PivotStrategy>(
+    v: &mut [T],
+    cmp: F,
+    strategy: &mut S,
+) -> usize {
+    let mut p = strategy.choose(v, &cmp);
+    v.swap(p, 0);
+
+    p = 0;
+    for i in 1..v.len() {
+        if cmp(&v[i], &v[p]) == Ordering::Less {
+            v.swap(p + 1, i);
+            v.swap(p, p + 1);
+            p += 1;
+        }
+    }
+    p
+}
+
+pub fn quick_sort<T: PartialOrd>(v: &mut [T]) {
+    quick_sort_by(v, partial_compare)
+}
+
+pub fn quick_sort_by<T, F: Fn(&T, &T) -> Ordering>(v: &mut [T], cmp: F) {
+    quick_sort_with(v, cmp, &mut SharedRandPivot)
+}
+
+//Any RngCore (eg. a seeded one, for reproducible runs, with no lock) or pivot strategy.
+pub fn quick_sort_with<T, F: Fn(&T, &T) -> Ordering, S: PivotStrategy>(
+    v: &mut [T],
+    cmp: F,
+    strategy: &mut S,
+) {
+    quick_sort_rec(v, &cmp, strategy)
+}
+
+fn quick_sort_rec<T, F: Fn(&T, &T) -> Ordering, S: PivotStrategy>(
+    v: &mut [T],
+    cmp: &F,
+    strategy: &mut S,
+) {
+    if v.len() <= 1 {
+        return;
+    }
+    let p = pivot(v, cmp, strategy);
+
+    let (a, b) = v.split_at_mut(p);
+    quick_sort_rec(a, cmp, strategy);
+    quick_sort_rec(&mut b[1..], cmp, strategy);
+}
+
+pub struct InsertSort;
+pub struct MergeSort;
+pub struct QuickSort;
+
+impl<T> Sorter<T> for InsertSort {
+    fn sort_by<F: Fn(&T, &T) -> Ordering + Sync>(&self, v: &mut [T], cmp: F) {
+        insert_sort_by(v, cmp)
+    }
+}
+
+impl<T: Clone> Sorter<T> for MergeSort {
+    fn sort_by<F: Fn(&T, &T) -> Ordering + Sync>(&self, v: &mut [T], cmp: F) {
+        let sorted = merge_sort_by(v.to_vec(), cmp);
+        v.clone_from_slice(&sorted);
+    }
+}
+
+impl<T> Sorter<T> for QuickSort {
+    fn sort_by<F: Fn(&T, &T) -> Ordering + Sync>(&self, v: &mut [T], cmp: F) {
+        quick_sort_by(v, cmp)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hands_on_algos::d2_3_quicksort::NintherPivot;
+    use hands_on_algos::d2_5_sorter::FloatOrder;
+
+    fn test_sorter<S: Sorter<i32>>(sorter: S) {
+        let mut v = vec![1, 34, 6, 12, 8, 100, 320, 66, 90, 2000, 45, 65, 120];
+
+        sorter.sort(&mut v);
+        assert_eq!(
+            v,
+            vec![1, 6, 8, 12, 34, 45, 65, 66, 90, 100, 120, 320, 2000]
+        );
+
+        sorter.sort_by(&mut v, |a, b| b.cmp(a));
+        assert_eq!(
+            v,
+            vec![2000, 320, 120, 100, 90, 66, 65, 45, 34, 12, 8, 6, 1]
+        );
+
+        sorter.sort_by_key(&mut v, |x| x % 10);
+        assert!(v.windows(2).all(|w| w[0] % 10 <= w[1] % 10));
+    }
+
+    #[test]
+    fn test_sorters() {
+        test_sorter(InsertSort);
+        test_sorter(MergeSort);
+        test_sorter(QuickSort);
+    }
+
+    #[test]
+    fn test_quick_sort_with() {
+        use hands_on_algos::d2_3_quicksort::{FirstPivot, MedianOfThreePivot};
+
+        let v = vec![1, 34, 6, 12, 8, 100, 320, 66, 90, 2000, 45, 65, 120];
+        let mut expected = v.clone();
+        expected.sort();
+
+        let mut v1 = v.clone();
+        quick_sort_with(&mut v1, |a, b| a.cmp(b), &mut FirstPivot);
+        assert_eq!(v1, expected);
+
+        let mut v2 = v.clone();
+        quick_sort_with(&mut v2, |a, b| a.cmp(b), &mut MedianOfThreePivot);
+        assert_eq!(v2, expected);
+
+        let mut v3 = v;
+        quick_sort_with(&mut v3, |a, b| a.cmp(b), &mut NintherPivot);
+        assert_eq!(v3, expected);
+    }
+
+    //NaN of both signs, -0/+0, infinities, subnormals and duplicates.
+    fn floats() -> Vec<f64> {
+        let mut v = vec![
+            1.5,
+            f64::NAN,
+            -0.0,
+            f64::INFINITY,
+            -1.5,
+            0.0,
+            -f64::NAN,
+            f64::NEG_INFINITY,
+            f64::MIN_POSITIVE / 2.0,
+            1.5,
+            -0.0,
+            f64::NAN,
+        ];
+        v.extend((0..500).map(|i| match i % 5 {
+            0 => f64::NAN,
+            1 => -0.0,
+            2 => f64::INFINITY,
+            _ => (i as f64 * 7.3) % 11.0 - 5.0,
+        }));
+        v
+    }
+
+    fn check_floats(sorted: &[f64], order: FloatOrder) {
+        let mut bits: Vec<u64> = sorted.iter().map(|x| x.to_bits()).collect();
+        let mut expected: Vec<u64> = floats().iter().map(|x| x.to_bits()).collect();
+        bits.sort_unstable();
+        expected.sort_unstable();
+        assert_eq!(bits, expected);
+        assert!(sorted
+            .windows(2)
+            .all(|w| order.compare(&w[0], &w[1]) != Ordering::Greater));
+    }
+
+    #[test]
+    fn test_sort_floats() {
+        let sorters: Vec<fn(&mut [f64], FloatOrder)> = vec![
+            |v, order| InsertSort.sort_floats(v, order),
+            |v, order| MergeSort.sort_floats(v, order),
+            |v, order| QuickSort.sort_floats(v, order),
+            |v, order| c_par_merge::ParMergeSort { threshold: 16 }.sort_floats(v, order),
+            |v, order| quick_sort_with(v, |a, b| order.compare(a, b), &mut NintherPivot),
+        ];
+        for sort in sorters {
+            for order in &[FloatOrder::Total, FloatOrder::NanFirst, FloatOrder::NanLast] {
+                let mut v = floats();
+                sort(&mut v, *order);
+                check_floats(&v, *order);
+            }
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use rand::prelude::*;
+use hands_on_algos::d2_15_sampling::shuffle;
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::fmt;
@@ -201,7 +201,7 @@ impl<T, E: Weighted, ID: Clone + Hash + Eq + fmt::Debug> Graph<T, E, ID> {
 
     pub fn iter_salesman(&self, start: ID) -> Option<Rc<Route<ID>>> {
         let mut bpath: Vec<ID> = self.data.keys().map(|k| k.clone()).collect();
-        bpath.shuffle(&mut rand::thread_rng());
+        shuffle(&mut bpath, &mut rand::thread_rng());
         for n in 0..bpath.len() {
             if bpath[n] == start {
                 bpath.swap(0, n);